@@ -0,0 +1,35 @@
+use ark_bls12_381::{Fr as F, G1Affine, G1Projective};
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use bulletproofs_rs::{msm::msm, pedersen_commitment::generate_n_random_points};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// The point-by-point accumulation every commit function used before the shared MSM path
+fn naive_commit(scalars: &[F], bases: &[G1Affine]) -> G1Affine {
+    let mut result = G1Affine::identity();
+    for (scalar, base) in scalars.iter().zip(bases.iter()) {
+        result = (result + *base * scalar).into_affine();
+    }
+    result
+}
+
+fn bench_commit(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let mut group = c.benchmark_group("commit");
+    for n in [64usize, 256, 1024] {
+        let bases = generate_n_random_points("bench".to_string(), n as i32);
+        let scalars: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+        // Both paths have to agree before their timings are worth comparing
+        assert_eq!(naive_commit(&scalars, &bases), msm(&scalars, &bases).unwrap().into_affine());
+
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |b, _| b.iter(|| naive_commit(&scalars, &bases)));
+        group.bench_with_input(BenchmarkId::new("msm", n), &n, |b, _| {
+            b.iter(|| -> G1Projective { msm(&scalars, &bases).unwrap() })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_commit);
+criterion_main!(benches);
@@ -0,0 +1,53 @@
+use ark_bls12_381::{g1, Fr as F, G1Affine};
+use ark_ec::CurveGroup;
+use ark_ff::{Field, UniformRand};
+use bulletproofs_rs::{
+    log_ipa_proof::{fold_points, prove},
+    pedersen_commitment::generate_n_random_points,
+    transcript::Transcript,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Folding with an affine conversion, and therefore a field inversion, for every point
+fn naive_fold(g_vec: &[G1Affine], u: &F) -> Vec<G1Affine> {
+    let u_inv = u.inverse().unwrap();
+    g_vec.chunks(2).map(|chunk| (chunk[0] * u + chunk[1] * u_inv).into_affine()).collect()
+}
+
+fn bench_fold(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let mut group = c.benchmark_group("fold");
+    for n in [1024usize, 4096] {
+        let g_vec = generate_n_random_points::<g1::Config>("bench".to_string(), n as i32);
+        let u = F::rand(&mut rng);
+
+        assert_eq!(naive_fold(&g_vec, &u), fold_points(g_vec.clone(), &u).unwrap());
+
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |b, _| b.iter(|| naive_fold(&g_vec, &u)));
+        group.bench_with_input(BenchmarkId::new("batch_normalized", n), &n, |b, _| {
+            b.iter(|| fold_points(g_vec.clone(), &u).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_prove(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let mut group = c.benchmark_group("prove");
+    group.sample_size(10);
+    for n in [256usize, 1024] {
+        let g_vec = generate_n_random_points::<g1::Config>("bench_g".to_string(), n as i32);
+        let h_vec = generate_n_random_points::<g1::Config>("bench_h".to_string(), n as i32);
+        let q = generate_n_random_points::<g1::Config>("bench_q".to_string(), 1)[0];
+        let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+        group.bench_with_input(BenchmarkId::new("inner_product_proof", n), &n, |bench, _| {
+            bench.iter(|| prove(&mut Transcript::new(b"bench"), &g_vec, &h_vec, &q, &a, &b).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fold, bench_prove);
+criterion_main!(benches);
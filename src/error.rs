@@ -0,0 +1,88 @@
+use std::fmt::{self, Display};
+
+/// Errors returned by the commitment functions, provers and verifiers of this crate. Malformed inputs are reported
+/// through these instead of panicking, so proofs and messages received from the network can be handled safely.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofError {
+    /// Two vectors that have to match in length, such as a vector and its generators, do not
+    LengthMismatch { expected: usize, found: usize },
+    /// Vectors are folded in halves, so their length has to be a power of two
+    NotPowerOfTwo(usize),
+    /// A challenge that has to be inverted is zero
+    ZeroChallenge,
+    /// A point is not on the curve or not in its prime order subgroup
+    InvalidPoint,
+    /// Bytes that do not hold an encoding of the expected type
+    InvalidEncoding,
+    /// A polynomial whose degree the protocol is not defined for
+    UnsupportedDegree(usize),
+    /// A range proof bit size outside of SUPPORTED_BIT_SIZES
+    UnsupportedBitSize(usize),
+    /// An input that needs at least one element is empty
+    EmptyInput,
+    /// The value to prove lies outside of the range or interval
+    ValueOutOfRange,
+    /// An interval whose lower bound is larger than its upper bound
+    InvalidInterval { min: i64, max: i64 },
+    /// The prover's assignment does not satisfy every constraint of the circuit
+    UnsatisfiedConstraint,
+    /// The prover allocated a variable without an assignment
+    MissingAssignment,
+    /// A constraint refers to a variable that was not allocated or committed in the circuit
+    UnknownVariable,
+    /// A message of the multi-party protocol arrived out of order
+    UnexpectedPosition { expected: usize, found: usize },
+    /// A party was assigned a position outside of the m parties of the multi-party protocol
+    PositionOutOfRange { position: usize, m: usize },
+    /// Proof shares of the multi-party protocol that do not match the commitments of their parties
+    MalformedShares(Vec<usize>),
+    /// Proofs of a batch that do not verify, given by their indices in the batch
+    BatchVerificationFailure(Vec<usize>),
+    /// The proof is well formed but does not verify
+    VerificationFailure,
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::LengthMismatch { expected, found } => {
+                write!(f, "Invalid vector lengths: expected {}, found {}", expected, found)
+            }
+            ProofError::NotPowerOfTwo(len) => write!(f, "Vector length must be a power of two, found {}", len),
+            ProofError::ZeroChallenge => write!(f, "Challenge must not be zero"),
+            ProofError::InvalidPoint => write!(f, "Point is not on the curve or not in the prime order subgroup"),
+            ProofError::InvalidEncoding => write!(f, "Invalid encoding"),
+            ProofError::UnsupportedDegree(degree) => write!(f, "Unsupported polynomial degree {}", degree),
+            ProofError::UnsupportedBitSize(n_bits) => write!(f, "Unsupported bit size {}", n_bits),
+            ProofError::EmptyInput => write!(f, "At least one element is required"),
+            ProofError::ValueOutOfRange => write!(f, "Value is out of range"),
+            ProofError::InvalidInterval { min, max } => write!(f, "Invalid interval [{}, {}]", min, max),
+            ProofError::UnsatisfiedConstraint => write!(f, "Constraint is not satisfied"),
+            ProofError::MissingAssignment => write!(f, "Missing variable assignment"),
+            ProofError::UnknownVariable => write!(f, "Variable is not allocated in the circuit"),
+            ProofError::UnexpectedPosition { expected, found } => {
+                write!(f, "Expected the message of position {}, received position {}", expected, found)
+            }
+            ProofError::PositionOutOfRange { position, m } => {
+                write!(f, "Position {} is out of range for {} parties", position, m)
+            }
+            ProofError::MalformedShares(positions) => {
+                write!(f, "Malformed proof shares from positions {:?}", positions)
+            }
+            ProofError::BatchVerificationFailure(indices) => {
+                write!(f, "Batch verification failed for proofs {:?}", indices)
+            }
+            ProofError::VerificationFailure => write!(f, "Proof verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+/// Checks that a vector has the length the protocol expects
+pub(crate) fn check_length(expected: usize, found: usize) -> Result<(), ProofError> {
+    if expected != found {
+        return Err(ProofError::LengthMismatch { expected, found });
+    }
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::{
+    error::{check_length, ProofError},
+    r1cs::{ConstraintSystem, LinearCombination, Variable},
+};
+
+// Building blocks for circuits of the r1cs module. Every gadget is generic over the constraint system, so the
+// same code builds the circuit for the Prover and the Verifier. Boolean results are linear combinations that
+// evaluate to 0 or 1 and can be passed straight into further gadgets.
+
+/// Constrains x to be 0 or 1 with the gate x * (1 - x) = 0
+pub fn boolean<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(cs: &mut CS, x: LinearCombination<F>) {
+    let (_, _, o) = cs.multiply(x.clone(), LinearCombination::from(Variable::ONE) - x);
+    cs.constrain(o.into());
+}
+
+/// Allocates a wire for the given bit and constrains it to be boolean
+pub fn allocate_bit<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    bit: Option<bool>,
+) -> Result<Variable<F>, ProofError> {
+    let (l, r, o) = cs.allocate_multiplier(bit.map(|b| (F::from(b), F::from(!b))))?;
+    // b * (1 - b) = 0 with the right wire constrained to 1 - b
+    cs.constrain(o.into());
+    cs.constrain(l + r - Variable::ONE);
+    Ok(l)
+}
+
+/// Splits v into n bits b_i with Σ_i 2^i * b_i = v, which also proves v lies in [0, 2^n)
+pub fn bit_decomposition<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    v: LinearCombination<F>,
+    n_bits: usize,
+) -> Result<Vec<Variable<F>>, ProofError> {
+    let value = cs.evaluate(&v).map(|value| value.into_bigint());
+
+    let mut bits = Vec::with_capacity(n_bits);
+    let mut sum = LinearCombination::default();
+    let mut exponent = F::ONE;
+    for i in 0..n_bits {
+        let bit = allocate_bit(cs, value.map(|value| value.get_bit(i)))?;
+        sum = sum + bit * exponent;
+        exponent.double_in_place();
+        bits.push(bit);
+    }
+    cs.constrain(sum - v);
+    Ok(bits)
+}
+
+/// Constrains v to lie in [0, 2^n)
+pub fn range<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    v: LinearCombination<F>,
+    n_bits: usize,
+) -> Result<(), ProofError> {
+    bit_decomposition(cs, v, n_bits)?;
+    Ok(())
+}
+
+/// Returns 1 if x is zero and 0 otherwise. The prover supplies x^-1 (or 0) as the wire inv, so that
+/// x * inv = 1 - is_zero and x * is_zero = 0.
+pub fn is_zero<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    x: LinearCombination<F>,
+) -> Result<LinearCombination<F>, ProofError> {
+    let value = cs.evaluate(&x);
+    let inverse = value.map(|value| value.inverse().unwrap_or(F::ZERO));
+
+    let (l, _, o) = cs.allocate_multiplier(value.zip(inverse))?;
+    cs.constrain(l - x.clone());
+
+    let is_zero = LinearCombination::from(Variable::ONE) - o;
+    let (_, _, x_is_zero) = cs.multiply(x, is_zero.clone());
+    cs.constrain(x_is_zero.into());
+    Ok(is_zero)
+}
+
+/// Returns 1 if a equals b and 0 otherwise
+pub fn is_equal<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) -> Result<LinearCombination<F>, ProofError> {
+    is_zero(cs, a - b)
+}
+
+/// Constrains a to equal b
+pub fn equal<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) {
+    cs.constrain(a - b);
+}
+
+/// Returns 1 if a < b and 0 otherwise, for a and b in [0, 2^n). The top bit of the n + 1 bit decomposition of
+/// b - a - 1 + 2^n is set exactly when b - a - 1 is non-negative.
+pub fn less_than<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+    n_bits: usize,
+) -> Result<LinearCombination<F>, ProofError> {
+    let two_n = F::from(2).pow([n_bits as u64]);
+    let shifted = b - a - Variable::ONE + two_n;
+    let bits = bit_decomposition(cs, shifted, n_bits + 1)?;
+    Ok(bits[n_bits].into())
+}
+
+/// a AND b = a * b for boolean a and b
+pub fn and<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) -> LinearCombination<F> {
+    let (_, _, o) = cs.multiply(a, b);
+    o.into()
+}
+
+/// a OR b = a + b - a * b for boolean a and b
+pub fn or<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) -> LinearCombination<F> {
+    let (_, _, o) = cs.multiply(a.clone(), b.clone());
+    a + b - o
+}
+
+/// a XOR b = a + b - 2 * a * b for boolean a and b
+pub fn xor<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) -> LinearCombination<F> {
+    let (_, _, o) = cs.multiply(a.clone(), b.clone());
+    a + b - o * F::from(2)
+}
+
+/// Returns a if the boolean condition is 1 and b if it is 0, as b + condition * (a - b)
+pub fn conditional_select<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    condition: LinearCombination<F>,
+    a: LinearCombination<F>,
+    b: LinearCombination<F>,
+) -> LinearCombination<F> {
+    let (_, _, o) = cs.multiply(condition, a - b.clone());
+    b + o
+}
+
+/// Constrains y to be a permutation of x. With a challenge z derived after every value is committed,
+/// Π_i (x_i - z) = Π_i (y_i - z) holds for a random z only if both lists hold the same values.
+pub fn shuffle<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    x: &[Variable<F>],
+    y: &[Variable<F>],
+) -> Result<(), ProofError> {
+    check_length(x.len(), y.len())?;
+    if x.is_empty() {
+        return Err(ProofError::EmptyInput);
+    }
+    if x.len() == 1 {
+        equal(cs, x[0].into(), y[0].into());
+        return Ok(());
+    }
+
+    let z: F = cs.transcript().challenge_scalar(b"shuffle_z");
+    let x_product = product(cs, x, &z);
+    let y_product = product(cs, y, &z);
+    equal(cs, x_product, y_product);
+    Ok(())
+}
+
+/// Π_i (x_i - z) as a chain of multiplication gates
+fn product<F: PrimeField, CS: ConstraintSystem<F> + ?Sized>(
+    cs: &mut CS,
+    x: &[Variable<F>],
+    z: &F,
+) -> LinearCombination<F> {
+    let (_, _, first) = cs.multiply(x[0] - *z, x[1] - *z);
+    x[2..].iter().fold(first.into(), |acc, x_i| {
+        let (_, _, o) = cs.multiply(acc, *x_i - *z);
+        o.into()
+    })
+}
@@ -0,0 +1,60 @@
+use ark_ec::CurveGroup;
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::ProofError,
+    pedersen_commitment::DeriveGenerators,
+    range_proof::{prove_range_aggregated, verify_range_aggregated, RangeProof, SUPPORTED_BIT_SIZES},
+};
+
+// A value v lies in [min, max] exactly when both v - min and max - v are non-negative. With n bits such that
+// max - min < 2^n, proving v - min and max - v in [0, 2^n) is enough, since their sum is max - min and neither
+// can wrap around. Both claims are proven by one aggregated range proof over commitments derived from C_v:
+//   C_v / G^min = G^(v - min) + B^γ
+//   G^max / C_v = G^(max - v) + B^-γ
+
+/// Proves that v lies in [min, max], returning the proof and the commitment C_v = G^v + B^γ it is verified against
+pub fn prove_interval<G: DeriveGenerators, R: RngCore + CryptoRng>(
+    v: i64,
+    gamma: G::ScalarField,
+    min: i64,
+    max: i64,
+    rng: &mut R,
+) -> Result<(RangeProof<G>, G), ProofError> {
+    let n_bits = interval_bit_size(min, max)?;
+    if v < min || v > max {
+        return Err(ProofError::ValueOutOfRange);
+    }
+
+    let above_min = (v as i128 - min as i128) as u64;
+    let below_max = (max as i128 - v as i128) as u64;
+    let (proof, _) = prove_range_aggregated(&[above_min, below_max], &[gamma, -gamma], n_bits, rng)?;
+
+    let (g, b) = G::pedersen_generators();
+    let c_v = (g * G::ScalarField::from(v) + b * gamma).into_affine();
+    Ok((proof, c_v))
+}
+
+/// Verifies that the value committed in C_v lies in [min, max]
+pub fn verify_interval<G: DeriveGenerators>(c_v: &G, proof: &RangeProof<G>, min: i64, max: i64) -> Result<(), ProofError> {
+    let n_bits = interval_bit_size(min, max)?;
+    let (g, _) = G::pedersen_generators();
+
+    let commitments = [
+        (*c_v - g * G::ScalarField::from(min)).into_affine(),
+        (g * G::ScalarField::from(max) - *c_v).into_affine(),
+    ];
+    verify_range_aggregated(&commitments, proof, n_bits)
+}
+
+/// The smallest supported bit size n with max - min < 2^n
+pub fn interval_bit_size(min: i64, max: i64) -> Result<usize, ProofError> {
+    if min > max {
+        return Err(ProofError::InvalidInterval { min, max });
+    }
+    let width = (max as i128 - min as i128) as u128;
+    SUPPORTED_BIT_SIZES
+        .into_iter()
+        .find(|n_bits| width >> n_bits == 0)
+        .ok_or(ProofError::UnsupportedBitSize(128 - width.leading_zeros() as usize))
+}
@@ -1,9 +1,23 @@
+pub mod error;
+pub mod gadgets;
+pub mod interval_proof;
 pub mod log_ipa_proof;
 pub mod log_proof_commitment;
+pub mod msm;
 pub mod pedersen_commitment;
 pub mod polynomial_commitment;
 pub mod random_ec_points;
+pub mod r1cs;
+pub mod range_proof;
+pub mod range_proof_mpc;
+#[cfg(feature = "ristretto")]
+pub mod ristretto;
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1;
+pub mod serialization;
+pub mod shuffle_proof;
 pub mod succinct_proof;
+pub mod transcript;
 pub mod vector_polynomial;
 pub mod zk_ipa;
 pub mod zk_mul;
@@ -1,134 +1,505 @@
+use std::{collections::HashMap, io::Read};
 
-use ark_bn254::{Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field};
+use ark_ff::{AdditiveGroup, Field, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate};
+use ark_std::{cfg_chunks, Zero};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    error::{check_length, ProofError},
+    msm::msm,
+    serialization::deserialize_vec,
+    transcript::Transcript,
+};
+
+/// Logarithmic size proof that P = <a, g> + <b, h> + <a, b> * q, consisting of the L and R commitments of every
+/// folding round and the two scalars that remain once the vectors are folded down to length one
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InnerProductProof<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex::vec"))]
+    pub l_vec: Vec<G>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex::vec"))]
+    pub r_vec: Vec<G>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub a: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub b: G::ScalarField,
+}
+
+impl<G: AffineRepr> Valid for InnerProductProof<G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.l_vec.check()?;
+        self.r_vec.check()?;
+        self.a.check()?;
+        self.b.check()
+    }
+}
 
-use crate::{random_ec_points::generate_random_field_element};
+// The L and R vectors are read without trusting their length prefixes, see deserialize_vec
+impl<G: AffineRepr> CanonicalDeserialize for InnerProductProof<G> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            l_vec: deserialize_vec(&mut reader, compress, validate)?,
+            r_vec: deserialize_vec(&mut reader, compress, validate)?,
+            a: G::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?,
+            b: G::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+/// Computes P = <a, g> + <b, h> + <a, b> * q, the commitment an inner product proof is verified against
+pub fn commit_inner_product<G: AffineRepr>(
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    a: &[G::ScalarField],
+    b: &[G::ScalarField],
+) -> Result<G, ProofError> {
+    check_length(a.len(), b.len())?;
+    Ok((commit(a, g_vec)? + commit(b, h_vec)? + *q * compute_inner_product(a, b)).into_affine())
+}
 
+pub fn prove<G: AffineRepr>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    a: &[G::ScalarField],
+    b: &[G::ScalarField],
+) -> Result<InnerProductProof<G>, ProofError> {
+    prove_statement(transcript, g_vec, h_vec, q, a, b, true)
+}
 
-pub fn log_ipa_proof(
-    a: Vec<F>,
-    b: Vec<F>,
-    g_vec: Vec<G1Affine>,
-    h_vec: Vec<G1Affine>,
-    q: G1Affine
-) -> bool {
+/// Proves the inner product for a P the transcript already determines, as in the range proof, where P combines
+/// commitments and scalars absorbed before the argument starts. P is left out of the transcript, so the verifier
+/// never has to compute it.
+pub(crate) fn prove_bound<G: AffineRepr>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    a: &[G::ScalarField],
+    b: &[G::ScalarField],
+) -> Result<InnerProductProof<G>, ProofError> {
+    prove_statement(transcript, g_vec, h_vec, q, a, b, false)
+}
 
-    let initial_inner_product = compute_inner_product(&a, &b);
-    println!("Initial inner product: {:?}", initial_inner_product);
+fn prove_statement<G: AffineRepr>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    a: &[G::ScalarField],
+    b: &[G::ScalarField],
+    absorb_p: bool,
+) -> Result<InnerProductProof<G>, ProofError> {
+    check_length(a.len(), b.len())?;
+    check_length(a.len(), g_vec.len())?;
+    check_length(a.len(), h_vec.len())?;
+    if !a.len().is_power_of_two() {
+        return Err(ProofError::NotPowerOfTwo(a.len()));
+    }
 
-    let mut q_vec = hadamard_product(&b, &vec![q.clone(); b.len()]);
+    transcript.domain_separator(b"inner_product_proof");
+    transcript.append_u64(b"n", a.len() as u64);
+    if absorb_p {
+        let p_com = commit_inner_product(g_vec, h_vec, q, a, b)?;
+        transcript.append_point(b"P", &p_com);
+    }
 
-    let mut p_com = (commit(&a, &g_vec) + commit(&b, &h_vec) + commit(&a, &q_vec)).into_affine();
-    println!("Initial p_com: {:?}", p_com);
+    let mut a_prime = a.to_vec();
+    let mut b_prime = b.to_vec();
 
-    let mut a_prime = a.clone();
-    let mut b_prime = b.clone();
-    
-    let mut g_prime = g_vec.clone();
-    let mut h_prime = h_vec.clone();
+    let mut g_prime = g_vec.to_vec();
+    let mut h_prime = h_vec.to_vec();
 
-    while a_prime.len() > 0{
+    let mut l_vec = Vec::new();
+    let mut r_vec = Vec::new();
 
-        if a_prime.len() == 1 {
-            let final_inner_product = a_prime[0] * b_prime[0];
-            println!("Final inner product: {:?}", final_inner_product);
-            println!("Inner products match: {}", initial_inner_product == final_inner_product);
+    while a_prime.len() > 1 {
+        let (l_com, r_com) = compute_l_r(a_prime.clone(), b_prime.clone(), g_prime.clone(), h_prime.clone(), q)?;
 
-            let final_result = commit(&a_prime, &g_prime) + commit(&b_prime, &h_prime) + (q * (a_prime[0] * b_prime[0]));
-            println!("Final comparison:");
-            println!("p_com: {:?}", p_com);
-            println!("final_result: {:?}", final_result.into_affine());
-            return p_com == final_result.into_affine();
-        } else {
+        // The challenge is bound to every commitment the prover has sent so far
+        transcript.append_point(b"L", &l_com);
+        transcript.append_point(b"R", &r_com);
+        let u: G::ScalarField = transcript.challenge_scalar(b"u");
+        let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
 
-            let current_inner_product = compute_inner_product(&a_prime, &b_prime);
-            println!("Current inner product: {:?}", current_inner_product);
+        l_vec.push(l_com);
+        r_vec.push(r_com);
 
-            let q_vec = hadamard_product(&b_prime, &vec![q.clone(); b_prime.len()]);
+        // g' = g_even * u^-1 + g_odd * u and h' = h_even * u + h_odd * u^-1
+        g_prime = fold_points(g_prime, &u_inv)?;
+        h_prime = fold_points(h_prime, &u)?;
 
-            let (l_com, r_com) = compute_l_r(a_prime.clone(), b_prime.clone(), g_prime.clone(), h_prime.clone(), q_vec.clone());
+        // a' = a_even * u + a_odd * u^-1 and b' = b_even * u^-1 + b_odd * u
+        a_prime = fold_field(a_prime, &u)?;
+        b_prime = fold_field(b_prime, &u_inv)?;
+    }
 
-            let u = generate_random_field_element();
-            println!("Generated u: {:?}", u);
+    Ok(InnerProductProof {
+        l_vec,
+        r_vec,
+        a: a_prime[0],
+        b: b_prime[0],
+    })
+}
 
-            // Verifier and prover both compute p_prime, g_prime and h_prime
-            let old_p_com = p_com.clone();
-            p_com = (l_com * u * u + p_com + r_com * u.inverse().unwrap() * u.inverse().unwrap()).into_affine();
-            println!("Updated p_com from {:?} to {:?}", old_p_com, p_com);
+/// Verifies an inner product proof against P using only public data. Instead of folding g and h round by round,
+/// the final check is a single multi-scalar multiplication:
+/// Σ_i a*s_i * g_i + Σ_i b/s_i * h_i + (a*b) * q - P - Σ_j (u_j^2 * L_j + u_j^-2 * R_j) = 0
+pub fn verify<G: AffineRepr>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    p: &G,
+    proof: &InnerProductProof<G>,
+) -> Result<(), ProofError> {
+    verification_equation(transcript, g_vec, h_vec, q, p, proof)?.check()
+}
 
-            g_prime = fold_points(g_prime.clone(), &u.inverse().unwrap());
-            h_prime = fold_points(h_prime.clone(), &u);
+/// A statement an inner product proof is verified against, together with the transcript state before the proof
+#[derive(Clone)]
+pub struct IpaStatement<'a, G: AffineRepr> {
+    pub transcript: Transcript,
+    pub g_vec: &'a [G],
+    pub h_vec: &'a [G],
+    pub q: G,
+    pub p: G,
+    pub proof: &'a InnerProductProof<G>,
+}
 
-            // prover computes a_prime and b_prime
-            let old_len = a_prime.len();
-            a_prime = fold_field(a_prime.clone(), &u);
-            b_prime = fold_field(b_prime.clone(), &u.inverse().unwrap());
-            println!("Vector lengths after folding: {} -> {}", old_len, a_prime.len());
+/// Verifies many inner product proofs at once by combining their verification equations with weights sampled from
+/// rng into one multi-scalar multiplication. If the batch is rejected, it is split in halves until the failing
+/// proofs are found, and their indices are returned in ProofError::BatchVerificationFailure.
+pub fn batch_verify<G: AffineRepr, R: RngCore + CryptoRng>(
+    statements: &[IpaStatement<G>],
+    rng: &mut R,
+) -> Result<(), ProofError> {
+    let equations = statements
+        .iter()
+        .map(|statement| {
+            let mut transcript = statement.transcript.clone();
+            verification_equation(
+                &mut transcript,
+                statement.g_vec,
+                statement.h_vec,
+                &statement.q,
+                &statement.p,
+                statement.proof,
+            )
+        })
+        .collect();
+    batch_check(equations, rng)
+}
+
+/// Bases and scalars of a verification equation, which holds when their multi-scalar multiplication is zero
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MsmEquation<G: AffineRepr> {
+    bases: Vec<G>,
+    scalars: Vec<G::ScalarField>,
+}
+
+impl<G: AffineRepr> MsmEquation<G> {
+    pub(crate) fn new() -> Self {
+        Self {
+            bases: Vec::new(),
+            scalars: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, base: G, scalar: G::ScalarField) {
+        self.bases.push(base);
+        self.scalars.push(scalar);
+    }
+
+    pub(crate) fn extend(&mut self, bases: &[G], scalars: impl IntoIterator<Item = G::ScalarField>) {
+        self.bases.extend_from_slice(bases);
+        self.scalars.extend(scalars);
+    }
+
+    /// Adds weight * other, so both equations hold at once with overwhelming probability for a random weight
+    pub(crate) fn combine(&mut self, other: &Self, weight: G::ScalarField) {
+        self.extend(&other.bases, other.scalars.iter().map(|scalar| *scalar * weight));
+    }
+
+    /// Returns VerificationFailure unless the equation holds
+    pub(crate) fn check(&self) -> Result<(), ProofError> {
+        if !self.is_satisfied() {
+            return Err(ProofError::VerificationFailure);
+        }
+        Ok(())
+    }
 
+    pub(crate) fn is_satisfied(&self) -> bool {
+        // Proofs sharing generators contribute to the same bases, so every base enters the MSM once
+        let mut indices: HashMap<G, usize> = HashMap::new();
+        let mut bases = Vec::new();
+        let mut scalars: Vec<G::ScalarField> = Vec::new();
+        for (base, scalar) in self.bases.iter().zip(self.scalars.iter()) {
+            let index = *indices.entry(*base).or_insert_with(|| {
+                bases.push(*base);
+                scalars.push(G::ScalarField::ZERO);
+                bases.len() - 1
+            });
+            scalars[index] += scalar;
         }
+        msm(&scalars, &bases).is_ok_and(|sum| sum.is_zero())
     }
-    return false
 }
 
+/// Checks every equation at once with weights sampled from rng, failing with the indices of the equations that
+/// could not be built or do not hold
+pub(crate) fn batch_check<G: AffineRepr, R: RngCore + CryptoRng>(
+    equations: Vec<Result<MsmEquation<G>, ProofError>>,
+    rng: &mut R,
+) -> Result<(), ProofError> {
+    let mut failures: Vec<usize> = equations
+        .iter()
+        .enumerate()
+        .filter(|(_, equation)| equation.is_err())
+        .map(|(index, _)| index)
+        .collect();
+    let candidates: Vec<(usize, MsmEquation<G>)> = equations
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, equation)| equation.ok().map(|equation| (index, equation)))
+        .collect();
+
+    find_failures(&candidates, &mut failures, rng);
+    failures.sort_unstable();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ProofError::BatchVerificationFailure(failures))
+    }
+}
+
+fn find_failures<G: AffineRepr, R: RngCore + CryptoRng>(
+    equations: &[(usize, MsmEquation<G>)],
+    failures: &mut Vec<usize>,
+    rng: &mut R,
+) {
+    if equations.is_empty() {
+        return;
+    }
+
+    let mut combined = MsmEquation::new();
+    for (_, equation) in equations {
+        combined.combine(equation, G::ScalarField::rand(rng));
+    }
+    if combined.is_satisfied() {
+        return;
+    }
+
+    if let [(index, _)] = equations {
+        failures.push(*index);
+        return;
+    }
+    let (left, right) = equations.split_at(equations.len() / 2);
+    find_failures(left, failures, rng);
+    find_failures(right, failures, rng);
+}
+
+/// Replays the verifier of a proof and returns the equation its final check consists of
+pub(crate) fn verification_equation<G: AffineRepr>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    q: &G,
+    p: &G,
+    proof: &InnerProductProof<G>,
+) -> Result<MsmEquation<G>, ProofError> {
+    let n = g_vec.len();
+    check_length(n, h_vec.len())?;
+    let (challenges, challenges_inv, s) = verification_scalars(transcript, n, p, proof)?;
+
+    let mut equation = MsmEquation::new();
+
+    // g_i and h_i fold down to Σ_i s_i * g_i and Σ_i s_i^-1 * h_i
+    equation.extend(g_vec, s.iter().map(|s_i| proof.a * s_i));
+    equation.extend(h_vec, s.iter().rev().map(|s_i_inv| proof.b * s_i_inv));
+
+    equation.push(*q, proof.a * proof.b);
+    equation.push(*p, -G::ScalarField::ONE);
+
+    // P' = P + Σ_j (L_j * u_j^2 + R_j * u_j^-2)
+    for ((l_com, r_com), (u, u_inv)) in proof
+        .l_vec
+        .iter()
+        .zip(proof.r_vec.iter())
+        .zip(challenges.iter().zip(challenges_inv.iter()))
+    {
+        equation.push(*l_com, -(*u * u));
+        equation.push(*r_com, -(*u_inv * u_inv));
+    }
+
+    Ok(equation)
+}
+
+/// Replays the transcript of a proof for vectors of length n and returns the challenges u_j, their inverses and
+/// the products s_i = Π_j u_j^(±1) with which g_i ends up in the folded generator, the exponent of u_j being +1
+/// exactly when bit j of i is set. Since s_(n-1-i) = s_i^-1, reversing s gives the coefficients of h.
+/// Fails if n is not a power of two or the proof does not have log2(n) rounds.
+#[allow(clippy::type_complexity)]
+pub fn verification_scalars<G: AffineRepr>(
+    transcript: &mut Transcript,
+    n: usize,
+    p: &G,
+    proof: &InnerProductProof<G>,
+) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>, Vec<G::ScalarField>), ProofError> {
+    replay_challenges(transcript, n, Some(p), proof)
+}
 
-pub fn compute_l_r(a: Vec<F>, b: Vec<F>, ec_points_g: Vec<G1Affine>, ec_points_h: Vec<G1Affine>, ec_points_q: Vec<G1Affine>) -> (G1Affine, G1Affine) {
-    let (l1_com, r1_com) = compute_secondary_diagonal(a.clone(), ec_points_g.clone());
-    let (l2_com, r2_com) = compute_secondary_diagonal(b.clone(), ec_points_h.clone());
-    let (l3_com, r3_com) = compute_secondary_diagonal(a.clone(), ec_points_q.clone());
+/// The verification scalars of a proof created with prove_bound, whose P is not part of the transcript
+#[allow(clippy::type_complexity)]
+pub(crate) fn verification_scalars_bound<G: AffineRepr>(
+    transcript: &mut Transcript,
+    n: usize,
+    proof: &InnerProductProof<G>,
+) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>, Vec<G::ScalarField>), ProofError> {
+    replay_challenges(transcript, n, None, proof)
+}
 
-    let l_com = (l1_com + l2_com + l3_com).into_affine();
-    let r_com = (r1_com + r2_com + r3_com).into_affine();
+#[allow(clippy::type_complexity)]
+fn replay_challenges<G: AffineRepr>(
+    transcript: &mut Transcript,
+    n: usize,
+    p: Option<&G>,
+    proof: &InnerProductProof<G>,
+) -> Result<(Vec<G::ScalarField>, Vec<G::ScalarField>, Vec<G::ScalarField>), ProofError> {
+    if !n.is_power_of_two() {
+        return Err(ProofError::NotPowerOfTwo(n));
+    }
+    // A vector of length n is folded log2(n) times
+    let rounds = n.trailing_zeros() as usize;
+    check_length(rounds, proof.l_vec.len())?;
+    check_length(rounds, proof.r_vec.len())?;
+
+    transcript.domain_separator(b"inner_product_proof");
+    transcript.append_u64(b"n", n as u64);
+    if let Some(p) = p {
+        transcript.append_point(b"P", p);
+    }
 
-    (l_com, r_com)
+    let mut challenges = Vec::with_capacity(rounds);
+    for (l_com, r_com) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+        transcript.append_point(b"L", l_com);
+        transcript.append_point(b"R", r_com);
+        challenges.push(transcript.challenge_scalar::<G::ScalarField>(b"u"));
+    }
+    let challenges_inv = challenges
+        .iter()
+        .map(|u| u.inverse().ok_or(ProofError::ZeroChallenge))
+        .collect::<Result<Vec<G::ScalarField>, _>>()?;
+
+    // s_0 = Π_j u_j^-1, and setting bit j of i multiplies s_i by u_j^2
+    let mut s = Vec::with_capacity(n);
+    s.push(challenges_inv.iter().product());
+    for i in 1..n {
+        let j = i.ilog2() as usize;
+        let s_i = s[i - (1 << j)] * challenges[j] * challenges[j];
+        s.push(s_i);
+    }
 
+    Ok((challenges, challenges_inv, s))
 }
 
-pub fn compute_secondary_diagonal(mut a: Vec<F>, mut ec_points: Vec<G1Affine>) -> (G1Affine, G1Affine) {
+pub fn compute_l_r<G: AffineRepr>(
+    a: Vec<G::ScalarField>,
+    b: Vec<G::ScalarField>,
+    ec_points_g: Vec<G>,
+    ec_points_h: Vec<G>,
+    q: &G,
+) -> Result<(G, G), ProofError> {
+    check_length(a.len(), b.len())?;
+    // The <a, b> * q term contributes q * <a_even, b_odd> to L and q * <a_odd, b_even> to R, which needs two
+    // scalar multiplications instead of committing to b ∘ q
+    let a_even_b_odd: G::ScalarField = a.chunks(2).zip(b.chunks(2)).map(|(a, b)| a[0] * b[1]).sum();
+    let a_odd_b_even: G::ScalarField = a.chunks(2).zip(b.chunks(2)).map(|(a, b)| a[1] * b[0]).sum();
+
+    let (g_diagonals, h_diagonals) = join(
+        || compute_secondary_diagonal(a, ec_points_g),
+        // b is folded with u^-1 and h with u, so the diagonals of <b, h> swap sides
+        || compute_secondary_diagonal(b, ec_points_h),
+    );
+    let ((l1_com, r1_com), (r2_com, l2_com)) = (g_diagonals?, h_diagonals?);
+
+    let l_com = (l1_com + l2_com + *q * a_even_b_odd).into_affine();
+    let r_com = (r1_com + r2_com + *q * a_odd_b_even).into_affine();
+
+    Ok((l_com, r_com))
+}
 
-    
-    let l: Vec<F> = a.chunks(2).map(|chunk| chunk[0]).collect();
-    let r: Vec<F> = a.chunks(2).map(|chunk| chunk[1]).collect();
+pub fn compute_secondary_diagonal<G: AffineRepr>(
+    a: Vec<G::ScalarField>,
+    ec_points: Vec<G>,
+) -> Result<(G, G), ProofError> {
+    check_length(ec_points.len(), a.len())?;
+    check_even(a.len())?;
 
-    let g1_vec: Vec<G1Affine> = ec_points.chunks(2).map(|chunk| chunk[0]).collect();
-    let g2_vec: Vec<G1Affine> = ec_points.chunks(2).map(|chunk| chunk[1]).collect();
-    
-    let l_com = commit(&l, &g2_vec);
-    let r_com = commit(&r, &g1_vec);
+    let l: Vec<G::ScalarField> = a.chunks(2).map(|chunk| chunk[0]).collect();
+    let r: Vec<G::ScalarField> = a.chunks(2).map(|chunk| chunk[1]).collect();
 
-    (l_com, r_com)
+    let g1_vec: Vec<G> = ec_points.chunks(2).map(|chunk| chunk[0]).collect();
+    let g2_vec: Vec<G> = ec_points.chunks(2).map(|chunk| chunk[1]).collect();
 
+    let (l_com, r_com) = join(|| commit(&l, &g2_vec), || commit(&r, &g1_vec));
+    Ok((l_com?, r_com?))
 }
 
 /// Computes the commitment C which is the sum of each generator g_i multiplied by the corresponding scalar a_i
-pub fn commit(
-    committing_vector: &Vec<F>,
-    g_vec: &Vec<G1Affine>,
-) -> G1Affine {
-    assert!(committing_vector.len() == g_vec.len(), "Invalid vector lengths");
-    let mut result: G1Affine = G1Affine::zero();
-    for (index, point) in committing_vector.iter().enumerate() {
-        // C += g_i * a_i
-        result = (result + g_vec[index] * point).into_affine();
-    }
-    result
+pub fn commit<G: AffineRepr>(committing_vector: &[G::ScalarField], g_vec: &[G]) -> Result<G, ProofError> {
+    // C = Σ_i g_i * a_i
+    Ok(msm(committing_vector, g_vec)?.into_affine())
 }
 
-pub fn hadamard_product(a_vec: &Vec<F>, g_vec: &Vec<G1Affine>) -> Vec<G1Affine> {
-    assert!(a_vec.len() == g_vec.len(), "Invalid vector lengths");
-    a_vec.iter().zip(g_vec.iter()).map(|(&a, &g)| (g * a).into_affine()).collect()
+pub fn hadamard_product<G: AffineRepr>(a_vec: &[G::ScalarField], g_vec: &[G]) -> Result<Vec<G>, ProofError> {
+    check_length(g_vec.len(), a_vec.len())?;
+    let result: Vec<G::Group> = a_vec.iter().zip(g_vec.iter()).map(|(&a, &g)| g * a).collect();
+    Ok(G::Group::normalize_batch(&result))
 }
 
-pub fn fold_points(g_vec: Vec<G1Affine>, u: &F) -> Vec<G1Affine> {
-    let u_inv = u.inverse().unwrap();
-    g_vec.chunks(2).map(|chunk| (chunk[0] * u + chunk[1] * u_inv).into_affine()).collect()
+pub fn fold_points<G: AffineRepr>(g_vec: Vec<G>, u: &G::ScalarField) -> Result<Vec<G>, ProofError> {
+    check_even(g_vec.len())?;
+    let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
+    // The folded points stay projective until the whole round is converted with a single batch inversion
+    let folded: Vec<G::Group> = cfg_chunks!(g_vec, 2).map(|chunk| chunk[0] * u + chunk[1] * u_inv).collect();
+    Ok(G::Group::normalize_batch(&folded))
 }
 
-pub fn fold_field(a_vec: Vec<F>, u: &F) -> Vec<F> {
-    let u_inv = u.inverse().unwrap();
-    a_vec.chunks(2).map(|chunk| chunk[0] * u + chunk[1] * u_inv).collect()
+pub fn fold_field<F: Field>(a_vec: Vec<F>, u: &F) -> Result<Vec<F>, ProofError> {
+    check_even(a_vec.len())?;
+    let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
+    Ok(cfg_chunks!(a_vec, 2).map(|chunk| chunk[0] * u + chunk[1] * u_inv).collect())
 }
 
-fn compute_inner_product(a: &Vec<F>, b: &Vec<F>) -> F {
+/// Vectors are folded pairwise, so an odd length cannot come from a power of two
+fn check_even(len: usize) -> Result<(), ProofError> {
+    if !len.is_multiple_of(2) {
+        return Err(ProofError::NotPowerOfTwo(len));
+    }
+    Ok(())
+}
+
+/// Runs both closures, on separate threads when the parallel feature is enabled
+pub(crate) fn join<A: Send, B: Send>(a: impl FnOnce() -> A + Send, b: impl FnOnce() -> B + Send) -> (A, B) {
+    #[cfg(feature = "parallel")]
+    return rayon::join(a, b);
+
+    #[cfg(not(feature = "parallel"))]
+    (a(), b())
+}
+
+fn compute_inner_product<F: Field>(a: &[F], b: &[F]) -> F {
     a.iter().zip(b.iter()).map(|(x, y)| *x * *y).sum()
-}
\ No newline at end of file
+}
@@ -1,55 +1,52 @@
-use std::error::Error;
-
-use ark_bn254::{Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_ff::{AdditiveGroup, Field};
 
 use crate::{
-    random_ec_points::generate_random_field_element,
-    succinct_proof::{commit, fold_field, fold_group},
+    error::ProofError,
+    succinct_proof::{commit, fold_field, fold_group, generate_challenge},
+    transcript::Transcript,
 };
 
 use super::succinct_proof::{commit_vector, verify_succinct_proof};
 
-pub fn verify_log_proof_of_committment(
-    commiting_vector: &mut Vec<F>,
-    g_vec: &mut Vec<G1Affine>,
-) -> bool {
+pub fn verify_log_proof_of_committment<G: AffineRepr>(
+    commiting_vector: &mut Vec<G::ScalarField>,
+    g_vec: &mut Vec<G>,
+    transcript: &mut Transcript,
+) -> Result<(), ProofError> {
     if commiting_vector.len() < 2_i32.pow(4) as usize {
-        commiting_vector.push(F::ZERO);
-        g_vec.push(G1Affine::zero())
+        commiting_vector.push(G::ScalarField::ZERO);
+        g_vec.push(G::zero())
     }
 
     let mut a = commiting_vector.clone();
     let mut g = g_vec.clone();
 
-    while a.len() > 0 {
-        let mut a_vec: Vec<F> = vec![F::ZERO];
-        let mut g_vec_last: Vec<G1Affine> = vec![G1Affine::zero()];
+    while !a.is_empty() {
+        let mut a_vec: Vec<G::ScalarField> = vec![G::ScalarField::ZERO];
+        let mut g_vec_last: Vec<G> = vec![G::zero()];
 
         if a.len() > 1 {
-            let (_a, _l, _r) = commit_vector(&mut a.clone(), &mut g.clone());
+            let (_a, _l, _r) = commit_vector(&mut a.clone(), &mut g.clone())?;
 
-            let u = generate_random_field_element();
+            let u = generate_challenge(transcript, &(_a, _l, _r));
 
-            a = fold_field(&mut a.clone(), &u);
+            a = fold_field(&mut a.clone(), &u)?;
 
-            dbg!(verify_succinct_proof(&(_a, _l, _r), &a, &u, &mut g));
-            
-            if !verify_succinct_proof(&(_a, _l, _r), &a, &u, &mut g) {
-                println!("Verification Failed");
-                break;
-            }
+            verify_succinct_proof(&(_a, _l, _r), &a, &u, &mut g)?;
 
-            g = fold_group(&mut g, &u.inverse().unwrap());
+            g = fold_group(&mut g, &u.inverse().ok_or(ProofError::ZeroChallenge)?)?;
 
             a_vec = a.clone();
             g_vec_last = g.clone();
         } else {
-            let _a = commit(&a_vec, &g_vec_last).unwrap();
-            return _a == g_vec_last[0] * a_vec[0];
+            let _a = commit(&a_vec, &g_vec_last)?;
+            if _a != (g_vec_last[0] * a_vec[0]).into_affine() {
+                return Err(ProofError::VerificationFailure);
+            }
+            return Ok(());
         }
     }
 
-    return false;
+    Err(ProofError::VerificationFailure)
 }
@@ -0,0 +1,10 @@
+use ark_ec::{AffineRepr, VariableBaseMSM};
+
+use crate::error::{check_length, ProofError};
+
+/// Computes Σ_i scalars_i * bases_i with arkworks' Pippenger multi-scalar multiplication. The sum is accumulated in
+/// projective coordinates and returned as such, so callers convert to affine once at the end.
+pub fn msm<G: AffineRepr>(scalars: &[G::ScalarField], bases: &[G]) -> Result<G::Group, ProofError> {
+    check_length(bases.len(), scalars.len())?;
+    Ok(G::Group::msm_unchecked(bases, scalars))
+}
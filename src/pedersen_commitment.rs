@@ -1,84 +1,104 @@
-use std::error::Error;
-
-use ark_bls12_381::{Bls12_381, Config, Fq, Fr as F, G1Affine};
-use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{Field, PrimeField};
-use ark_poly::{
-    polynomial,
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr, CurveGroup,
 };
-use ark_std::{rand, One};
+use ark_ff::{Field, PrimeField};
 use blake3::{self, Hash};
 
-pub fn pedersen_commitment(
-    committing_vector: &Vec<F>,
-    g_vec: &Vec<G1Affine>,
-    blinding_factor: F,
-) -> Result<G1Affine, Box<dyn Error>> {
+use crate::{
+    error::{check_length, ProofError},
+    msm::msm,
+    random_ec_points,
+};
+
+/// A curve whose generators are derived from seeds, which is what the range proofs need to run on it
+pub trait DeriveGenerators: AffineRepr {
+    /// Derives num_point generators from the seed, the same ones on every call
+    fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Self>;
+
+    /// The generators G and B of value commitments C = G^v + B^γ
+    fn pedersen_generators() -> (Self, Self) {
+        let pedersen = Self::generate_n_random_points("pedersen".to_string(), 2);
+        (pedersen[0], pedersen[1])
+    }
+}
+
+impl DeriveGenerators for Affine<ark_bls12_381::g1::Config> {
+    fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Self> {
+        generate_n_random_points::<ark_bls12_381::g1::Config>(seed, num_point)
+    }
+}
+
+impl DeriveGenerators for Affine<ark_bn254::g1::Config> {
+    fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Self> {
+        random_ec_points::generate_n_random_points::<ark_bn254::g1::Config>(seed, num_point)
+    }
+}
+
+pub fn pedersen_commitment<G: AffineRepr>(
+    committing_vector: &[G::ScalarField],
+    g_vec: &[G],
+    blinding_factor: G::ScalarField,
+) -> Result<G, ProofError> {
     if (committing_vector.len() + 1) > g_vec.len() {
-        return Err("Invalid vector lengths".into());
+        return Err(ProofError::LengthMismatch {
+            expected: committing_vector.len() + 1,
+            found: g_vec.len(),
+        });
     }
     // Pedersen commitment: C = ∑(v_i * G_i) + r * H
     // where v_i are the values, G_i are the generators, r is the blinding factor, and H is the last generator
-    let mut result: G1Affine = G1Affine::zero();
-    for (index, point) in committing_vector.iter().enumerate() {
-        // Accumulate v_i * G_i terms
-        result = (result.into_group() + g_vec[index] * point).into_affine();
-    }
+    let result = msm(committing_vector, &g_vec[..committing_vector.len()])?;
     // Add blinding factor term: r * H
-    result = (result.into_group() + *g_vec.last().unwrap() * blinding_factor).into();
-    Ok(result)
+    Ok((result + g_vec[g_vec.len() - 1] * blinding_factor).into_affine())
 }
 
-pub fn commit(
-    committing_vector: &Vec<F>,
-    g_vec: &Vec<G1Affine>,
-) -> Result<G1Affine, Box<dyn Error>> {
-    if committing_vector.len() != g_vec.len() {
-        return Err("Invalid vector lengths".into());
-    }
+pub fn commit<G: AffineRepr>(committing_vector: &[G::ScalarField], g_vec: &[G]) -> Result<G, ProofError> {
+    check_length(g_vec.len(), committing_vector.len())?;
     // Simple commitment without blinding: C = ∑(v_i * G_i)
-    let mut result: G1Affine = G1Affine::zero();
-    for (index, point) in committing_vector.iter().enumerate() {
-        // Accumulate v_i * G_i terms
-        result = (result + g_vec[index] * point).into_affine();
-    }
-    Ok(result)
+    Ok(msm(committing_vector, g_vec)?.into_affine())
 }
 
-fn generate_random_point(seed: String) -> (G1Affine, Hash) {
+fn generate_random_point<P: SWCurveConfig>(seed: String) -> (Affine<P>, Hash)
+where
+    P::BaseField: PrimeField,
+{
     let hash = blake3::hash(seed.as_bytes());
     let next_hash = blake3::hash(hash.as_bytes());
-    let mut x = Fq::from_le_bytes_mod_order(hash.as_bytes());
+    let mut x = P::BaseField::from_le_bytes_mod_order(hash.as_bytes());
     let mut y;
 
     loop {
-        if let Some(y_value) = find_y_for_x(x) {
+        if let Some(y_value) = find_y_for_x::<P>(x) {
             y = y_value;
-            let point = G1Affine::new_unchecked(x, y);
+            let point = Affine::<P>::new_unchecked(x, y);
             if point.is_on_curve() {
-                return (point, next_hash);
+                // Curves with a cofactor such as G1 of BLS12-381 need the point mapped into the prime order subgroup
+                return (point.clear_cofactor(), next_hash);
             }
         }
-        x = x + Fq::from(1);
+        x += P::BaseField::from(1);
     }
 }
 
-pub fn generate_n_random_points(seed: String, num_point: i32) -> Vec<G1Affine> {
-    let mut random_points = Vec::<G1Affine>::new();
+/// Derives num_point generators of the curve from the seed by hashing to x coordinates with BLAKE3
+pub fn generate_n_random_points<P: SWCurveConfig>(seed: String, num_point: i32) -> Vec<Affine<P>>
+where
+    P::BaseField: PrimeField,
+{
+    let mut random_points = Vec::<Affine<P>>::new();
     let mut current_seed = seed;
     for _ in 0..num_point {
-        let (point, next_seed) = generate_random_point(current_seed);
+        let (point, next_seed) = generate_random_point::<P>(current_seed);
         random_points.push(point);
         current_seed = next_seed.to_string();
     }
     random_points
 }
 
-fn find_y_for_x(x: Fq) -> Option<Fq> {
-    // BLS12-381 curve equation: y² = x³ + 4
+fn find_y_for_x<P: SWCurveConfig>(x: P::BaseField) -> Option<P::BaseField> {
+    // Short Weierstrass curve equation: y² = x³ + a·x + b, which is y² = x³ + 4 on BLS12-381
     let x_cubed = x * x * x;
-    let rhs = x_cubed + Fq::from(4);
+    let rhs = x_cubed + P::mul_by_a(x) + P::COEFF_B;
     rhs.sqrt()
 }
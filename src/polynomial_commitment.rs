@@ -1,28 +1,22 @@
-use std::error::Error;
-
-use ark_bls12_381::{Bls12_381, Config, Fq, Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
-use ark_poly::{
-    polynomial,
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+
+use crate::{
+    error::{check_length, ProofError},
+    transcript::Transcript,
 };
-use num_bigint::BigInt;
 
-pub fn commit_polynomial(
-    poly: &DensePolynomial<F>,
-    gammas: &Vec<F>,
-    g: &G1Affine,
-    b: &G1Affine,
-) -> Result<Vec<G1Affine>, Box<dyn Error>> {
+pub fn commit_polynomial<G: AffineRepr>(
+    poly: &DensePolynomial<G::ScalarField>,
+    gammas: &[G::ScalarField],
+    g: &G,
+    b: &G,
+) -> Result<Vec<G>, ProofError> {
     let coeffs = Vec::from(poly.coeffs());
-    if coeffs.len() != gammas.len() {
-        return Err("Invalid gammas length".into());
-    }
-    let mut results = Vec::<G1Affine>::new();
+    check_length(coeffs.len(), gammas.len())?;
+    let mut results = Vec::<G>::new();
     for (index, coeff) in coeffs.into_iter().enumerate() {
-        dbg!((index, &coeff));
         // For each coefficient c_i and random value γ_i:
         // Compute commitment_i = c_i * G + γ_i * B
         // This creates a Pedersen commitment for each coefficient
@@ -32,41 +26,54 @@ pub fn commit_polynomial(
     Ok(results)
 }
 
-pub fn generate_proof(gammas: &Vec<F>, u: &F) -> F {
+/// Absorbs the coefficient commitments into the transcript and derives the evaluation point u from them
+pub fn generate_challenge<G: AffineRepr>(transcript: &mut Transcript, commitments: &[G]) -> G::ScalarField {
+    transcript.domain_separator(b"polynomial_commitment");
+    transcript.append_u64(b"n", commitments.len() as u64);
+    for commitment in commitments {
+        transcript.append_point(b"C", commitment);
+    }
+    transcript.challenge_scalar(b"u")
+}
+
+pub fn generate_proof<F: Field>(gammas: &[F], u: &F) -> F {
     let mut proof = F::ZERO;
     // Compute π = Σ(γ_i * u^i) for i from 0 to n-1
     // This aggregates the random values (gammas) with powers of the evaluation point
     gammas
         .iter()
         .enumerate()
-        .for_each(|(index, gamma)| proof = proof + (*gamma * u.pow(&[index as u64])));
+        .for_each(|(index, gamma)| proof += *gamma * u.pow([index as u64]));
 
     proof
 }
 
-pub fn verify(
-    commitments: &Vec<G1Affine>,
-    g: &G1Affine,
-    b: &G1Affine,
-    u: &F,
-    f_u: &F,
-    proof: &F,
-) -> bool {
-    let mut lhs = G1Affine::zero();
+pub fn verify<G: AffineRepr>(
+    commitments: &[G],
+    g: &G,
+    b: &G,
+    u: &G::ScalarField,
+    f_u: &G::ScalarField,
+    proof: &G::ScalarField,
+) -> Result<(), ProofError> {
+    let mut lhs = G::zero();
     // Left-hand side: Σ(C_i * u^i) for i from 0 to n-1
     // Where C_i are the commitments and u is the evaluation point
     commitments
         .iter()
         .enumerate()
         .for_each(|(index, commitment)| {
-            let u_i = u.pow(&[index as u64]);
-            lhs = (lhs + (*commitment * u_i).into_affine()).into();
+            let u_i = u.pow([index as u64]);
+            lhs = (lhs + (*commitment * u_i).into_affine()).into_affine();
         });
 
     // Right-hand side: f(u) * G + π * B
     // Where f(u) is the polynomial evaluated at u, and π is the proof
-    let rhs = ((*g * f_u) + (*b * proof)).into_affine();
+    let rhs = ((*g * *f_u) + (*b * *proof)).into_affine();
 
     // Verification succeeds if LHS = RHS
-    lhs == rhs
+    if lhs != rhs {
+        return Err(ProofError::VerificationFailure);
+    }
+    Ok(())
 }
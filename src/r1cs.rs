@@ -0,0 +1,633 @@
+use std::{
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::ProofError,
+    log_ipa_proof::{self, InnerProductProof},
+    pedersen_commitment::DeriveGenerators,
+    range_proof::{powers, scale, scaled_h_vec, RangeProofGenerators},
+    transcript::Transcript,
+    vector_polynomial::{Coeff, InnerProduct, VectorPolynomial},
+    zk_ipa::inner_product_base,
+    zk_mul::generate_random_field_element,
+};
+
+// Arithmetic circuit proofs following section 5 of the Bulletproofs paper. A circuit consists of n multiplication
+// gates a_L ∘ a_R = a_O and linear constraints over the gate wires and the committed values v, which after
+// flattening with powers of a challenge z read
+//   <w_L, a_L> + <w_R, a_R> + <w_O, a_O> = <w_V, v> + w_c
+// The prover commits to the wires and proves both relations with
+//   l(X) = (a_L + y^-n ∘ w_R)*X + a_O*X^2 + s_L*X^3
+//   r(X) = (w_O - y^n) + (y^n ∘ a_R + w_L)*X + y^n ∘ s_R*X^3
+// whose inner product has the constant t_2 = <w_V, v> + w_c + δ(y, z) in front of X^2, where δ(y, z) = <y^-n ∘ w_R, w_L>.
+
+/// A wire of the circuit: a committed value, one of the three wires of a multiplication gate, or the constant 1.
+/// The scalar field F is carried along so that arithmetic on wires yields a LinearCombination<F>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variable<F> {
+    Committed(usize),
+    MultiplierLeft(usize),
+    MultiplierRight(usize),
+    MultiplierOutput(usize),
+    One(PhantomData<F>),
+}
+
+impl<F> Variable<F> {
+    /// The wire of the constant 1
+    pub const ONE: Self = Variable::One(PhantomData);
+}
+
+/// The left, right and output wires of a multiplication gate
+pub type MultiplierWires<F> = (Variable<F>, Variable<F>, Variable<F>);
+
+/// Σ_i c_i * variable_i, which a constraint requires to be zero
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinearCombination<F> {
+    pub terms: Vec<(Variable<F>, F)>,
+}
+
+impl<F: PrimeField> From<Variable<F>> for LinearCombination<F> {
+    fn from(variable: Variable<F>) -> Self {
+        Self {
+            terms: vec![(variable, F::ONE)],
+        }
+    }
+}
+
+impl<F: PrimeField> From<F> for LinearCombination<F> {
+    fn from(constant: F) -> Self {
+        Self {
+            terms: vec![(Variable::ONE, constant)],
+        }
+    }
+}
+
+impl<F> FromIterator<(Variable<F>, F)> for LinearCombination<F> {
+    fn from_iter<I: IntoIterator<Item = (Variable<F>, F)>>(iter: I) -> Self {
+        Self {
+            terms: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<F, L: Into<LinearCombination<F>>> Add<L> for LinearCombination<F> {
+    type Output = LinearCombination<F>;
+
+    fn add(mut self, rhs: L) -> Self::Output {
+        self.terms.extend(rhs.into().terms);
+        self
+    }
+}
+
+impl<F: PrimeField, L: Into<LinearCombination<F>>> Sub<L> for LinearCombination<F> {
+    type Output = LinearCombination<F>;
+
+    fn sub(self, rhs: L) -> Self::Output {
+        self + -rhs.into()
+    }
+}
+
+impl<F: PrimeField> Neg for LinearCombination<F> {
+    type Output = LinearCombination<F>;
+
+    fn neg(self) -> Self::Output {
+        self.terms.into_iter().map(|(variable, c)| (variable, -c)).collect()
+    }
+}
+
+impl<F: PrimeField> Mul<F> for LinearCombination<F> {
+    type Output = LinearCombination<F>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        self.terms.into_iter().map(|(variable, c)| (variable, c * rhs)).collect()
+    }
+}
+
+impl<F: PrimeField, L: Into<LinearCombination<F>>> Add<L> for Variable<F> {
+    type Output = LinearCombination<F>;
+
+    fn add(self, rhs: L) -> Self::Output {
+        LinearCombination::from(self) + rhs
+    }
+}
+
+impl<F: PrimeField, L: Into<LinearCombination<F>>> Sub<L> for Variable<F> {
+    type Output = LinearCombination<F>;
+
+    fn sub(self, rhs: L) -> Self::Output {
+        LinearCombination::from(self) - rhs
+    }
+}
+
+impl<F: PrimeField> Neg for Variable<F> {
+    type Output = LinearCombination<F>;
+
+    fn neg(self) -> Self::Output {
+        -LinearCombination::from(self)
+    }
+}
+
+impl<F: PrimeField> Mul<F> for Variable<F> {
+    type Output = LinearCombination<F>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        LinearCombination::from(self) * rhs
+    }
+}
+
+/// Builds a circuit. Gadgets are written once against this trait and run unchanged by the Prover, which knows
+/// the assignments of every wire, and by the Verifier, which does not.
+pub trait ConstraintSystem<F: PrimeField> {
+    /// The transcript of the proof, for gadgets that derive challenges from the committed values
+    fn transcript(&mut self) -> &mut Transcript;
+
+    /// The value of the linear combination, known only to the prover
+    fn evaluate(&self, lc: &LinearCombination<F>) -> Option<F>;
+
+    /// Adds a multiplication gate whose inputs are constrained to the two linear combinations, returning the
+    /// left, right and output wires
+    fn multiply(
+        &mut self,
+        left: LinearCombination<F>,
+        right: LinearCombination<F>,
+    ) -> MultiplierWires<F>;
+
+    /// Allocates a single wire. Two consecutive allocations share one multiplication gate.
+    fn allocate(&mut self, assignment: Option<F>) -> Result<Variable<F>, ProofError>;
+
+    /// Adds a multiplication gate with unconstrained inputs, returning the left, right and output wires
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(F, F)>,
+    ) -> Result<MultiplierWires<F>, ProofError>;
+
+    /// Requires the linear combination to evaluate to zero
+    fn constrain(&mut self, lc: LinearCombination<F>);
+}
+
+/// Proof that the wires of a circuit can be assigned so every gate and constraint holds for the committed values
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct R1csProof<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_ai: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_ao: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_s: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t1: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t3: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t4: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t5: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t6: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub tau_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub mu: G::ScalarField,
+    pub ipa_proof: InnerProductProof<G>,
+}
+
+pub struct Prover<'a, G: AffineRepr> {
+    transcript: &'a mut Transcript,
+    v: Vec<G::ScalarField>,
+    v_blinding: Vec<G::ScalarField>,
+    a_l: Vec<G::ScalarField>,
+    a_r: Vec<G::ScalarField>,
+    a_o: Vec<G::ScalarField>,
+    constraints: Vec<LinearCombination<G::ScalarField>>,
+    pending_multiplier: Option<usize>,
+}
+
+impl<'a, G: DeriveGenerators> Prover<'a, G> {
+    pub fn new(transcript: &'a mut Transcript) -> Self {
+        transcript.domain_separator(b"r1cs");
+        Self {
+            transcript,
+            v: Vec::new(),
+            v_blinding: Vec::new(),
+            a_l: Vec::new(),
+            a_r: Vec::new(),
+            a_o: Vec::new(),
+            constraints: Vec::new(),
+            pending_multiplier: None,
+        }
+    }
+
+    /// Commits to v with the blinding factor γ, returning C_v = G^v + B^γ and the variable of v in the circuit
+    pub fn commit(&mut self, v: G::ScalarField, gamma: G::ScalarField) -> (G, Variable<G::ScalarField>) {
+        let (g, h) = G::pedersen_generators();
+        let c_v = (g * v + h * gamma).into_affine();
+        self.transcript.append_point(b"V", &c_v);
+
+        self.v.push(v);
+        self.v_blinding.push(gamma);
+        (c_v, Variable::Committed(self.v.len() - 1))
+    }
+
+    /// Fails on variables that were not allocated or committed by this prover
+    fn eval(&self, lc: &LinearCombination<G::ScalarField>) -> Result<G::ScalarField, ProofError> {
+        lc.terms
+            .iter()
+            .map(|(variable, c)| {
+                let value = match variable {
+                    Variable::Committed(i) => self.v.get(*i),
+                    Variable::MultiplierLeft(i) => self.a_l.get(*i),
+                    Variable::MultiplierRight(i) => self.a_r.get(*i),
+                    Variable::MultiplierOutput(i) => self.a_o.get(*i),
+                    Variable::One(_) => Some(&G::ScalarField::ONE),
+                };
+                value.map(|value| *c * value).ok_or(ProofError::UnknownVariable)
+            })
+            .sum()
+    }
+
+    /// Proves the circuit built so far, sampling the blinding factors from rng. The number of gates is padded to a
+    /// power of two with zero gates.
+    pub fn prove<R: RngCore + CryptoRng>(self, rng: &mut R) -> Result<R1csProof<G>, ProofError> {
+        for lc in self.constraints.iter() {
+            if self.eval(lc)? != G::ScalarField::ZERO {
+                return Err(ProofError::UnsatisfiedConstraint);
+            }
+        }
+        let num_multipliers = self.a_l.len();
+
+        let n = self.a_l.len().next_power_of_two();
+        let gens = RangeProofGenerators::<G>::new(n);
+
+        let mut a_l = self.a_l;
+        let mut a_r = self.a_r;
+        let mut a_o = self.a_o;
+        a_l.resize(n, G::ScalarField::ZERO);
+        a_r.resize(n, G::ScalarField::ZERO);
+        a_o.resize(n, G::ScalarField::ZERO);
+        let (a_l, a_r, a_o) = (Coeff::new(a_l), Coeff::new(a_r), Coeff::new(a_o));
+
+        let transcript = self.transcript;
+        transcript.append_u64(b"m", self.v.len() as u64);
+        transcript.append_u64(b"n", n as u64);
+
+        let alpha: G::ScalarField = generate_random_field_element(rng);
+        let beta: G::ScalarField = generate_random_field_element(rng);
+        let rho: G::ScalarField = generate_random_field_element(rng);
+        let s_l = Coeff::random(n, rng);
+        let s_r = Coeff::random(n, rng);
+
+        // A_I = <a_L, g> + <a_R, h> + B^α, A_O = <a_O, g> + B^β, S = <s_L, g> + <s_R, h> + B^ρ
+        let c_ai = (a_l.commit(&gens.g_vec)? + a_r.commit(&gens.h_vec)? + gens.b * alpha).into_affine();
+        let c_ao = (a_o.commit(&gens.g_vec)? + gens.b * beta).into_affine();
+        let c_s = (s_l.commit(&gens.g_vec)? + s_r.commit(&gens.h_vec)? + gens.b * rho).into_affine();
+
+        transcript.append_point(b"A_I", &c_ai);
+        transcript.append_point(b"A_O", &c_ao);
+        transcript.append_point(b"S", &c_s);
+        let y: G::ScalarField = transcript.challenge_scalar(b"y");
+        let z: G::ScalarField = transcript.challenge_scalar(b"z");
+
+        let weights = flatten_constraints(&self.constraints, &z, n, num_multipliers, self.v.len())?;
+        let y_n = powers(&y, n);
+        let y_inv_n = powers(&y.inverse().ok_or(ProofError::ZeroChallenge)?, n);
+
+        let l_x = VectorPolynomial::new(vec![
+            Coeff::zero(n),
+            a_l.checked_add(&y_inv_n.checked_mul(&weights.w_r)?)?,
+            a_o,
+            s_l,
+        ])?;
+        let r_x = VectorPolynomial::new(vec![
+            weights.w_o.checked_add(&scale(&y_n, &-G::ScalarField::ONE))?,
+            y_n.checked_mul(&a_r)?.checked_add(&weights.w_l)?,
+            Coeff::zero(n),
+            y_n.checked_mul(&s_r)?,
+        ])?;
+
+        let t_poly = l_x.checked_mul(&r_x)?;
+        let t_i = |i: usize| t_poly.coeffs.get(i).copied().unwrap_or(G::ScalarField::ZERO);
+
+        // T_i = G^t_i + B^τ_i for every coefficient of t(X) except t_2, which is bound to the committed values
+        let tau: Vec<(usize, G::ScalarField)> =
+            [1, 3, 4, 5, 6].into_iter().map(|i| (i, generate_random_field_element(rng))).collect();
+        let c_t: Vec<G> = tau
+            .iter()
+            .map(|(i, tau_i)| (gens.g * t_i(*i) + gens.b * tau_i).into_affine())
+            .collect();
+
+        for (label, c_ti) in T_LABELS.iter().zip(c_t.iter()) {
+            transcript.append_point(label, c_ti);
+        }
+        let x: G::ScalarField = transcript.challenge_scalar(b"x");
+
+        let l = l_x.evaluate(&x);
+        let r = r_x.evaluate(&x);
+        let t_x = l.inner_product(&r)?;
+        // τ_x = Σ_i τ_i*x^i + x^2*<w_V, γ> blinds t(x), μ = α*x + β*x^2 + ρ*x^3 blinds l(x) and r(x)
+        let tau_x = tau.iter().map(|(i, tau_i)| *tau_i * x.pow([*i as u64])).sum::<G::ScalarField>()
+            + x * x * weights.w_v.inner_product(&Coeff::new(self.v_blinding))?;
+        let mu = alpha * x + beta * x * x + rho * x * x * x;
+
+        let q = inner_product_base(transcript, &t_x, &mu, &tau_x, &gens.g);
+        let h_prime = scaled_h_vec(&gens.h_vec, &y)?;
+        let ipa_proof = log_ipa_proof::prove(transcript, &gens.g_vec, &h_prime, &q, &l.0, &r.0)?;
+
+        Ok(R1csProof {
+            c_ai,
+            c_ao,
+            c_s,
+            c_t1: c_t[0],
+            c_t3: c_t[1],
+            c_t4: c_t[2],
+            c_t5: c_t[3],
+            c_t6: c_t[4],
+            t_x,
+            tau_x,
+            mu,
+            ipa_proof,
+        })
+    }
+}
+
+impl<G: DeriveGenerators> ConstraintSystem<G::ScalarField> for Prover<'_, G> {
+    fn transcript(&mut self) -> &mut Transcript {
+        self.transcript
+    }
+
+    fn evaluate(&self, lc: &LinearCombination<G::ScalarField>) -> Option<G::ScalarField> {
+        self.eval(lc).ok()
+    }
+
+    fn multiply(
+        &mut self,
+        mut left: LinearCombination<G::ScalarField>,
+        mut right: LinearCombination<G::ScalarField>,
+    ) -> MultiplierWires<G::ScalarField> {
+        // An unknown variable leaves the gate unassigned, and prove reports it when it evaluates the constraints
+        let l = self.eval(&left).unwrap_or(G::ScalarField::ZERO);
+        let r = self.eval(&right).unwrap_or(G::ScalarField::ZERO);
+        let (l_var, r_var, o_var) = self.push_multiplier(l, r);
+
+        left.terms.push((l_var, -G::ScalarField::ONE));
+        right.terms.push((r_var, -G::ScalarField::ONE));
+        self.constrain(left);
+        self.constrain(right);
+        (l_var, r_var, o_var)
+    }
+
+    fn allocate(&mut self, assignment: Option<G::ScalarField>) -> Result<Variable<G::ScalarField>, ProofError> {
+        let value = assignment.ok_or(ProofError::MissingAssignment)?;
+        match self.pending_multiplier.take() {
+            None => {
+                let (l_var, _, _) = self.push_multiplier(value, G::ScalarField::ZERO);
+                self.pending_multiplier = Some(self.a_l.len() - 1);
+                Ok(l_var)
+            }
+            Some(i) => {
+                self.a_r[i] = value;
+                self.a_o[i] = self.a_l[i] * value;
+                Ok(Variable::MultiplierRight(i))
+            }
+        }
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        input_assignments: Option<(G::ScalarField, G::ScalarField)>,
+    ) -> Result<MultiplierWires<G::ScalarField>, ProofError> {
+        let (l, r) = input_assignments.ok_or(ProofError::MissingAssignment)?;
+        Ok(self.push_multiplier(l, r))
+    }
+
+    fn constrain(&mut self, lc: LinearCombination<G::ScalarField>) {
+        self.constraints.push(lc);
+    }
+}
+
+impl<G: AffineRepr> Prover<'_, G> {
+    fn push_multiplier(&mut self, l: G::ScalarField, r: G::ScalarField) -> MultiplierWires<G::ScalarField> {
+        let i = self.a_l.len();
+        self.a_l.push(l);
+        self.a_r.push(r);
+        self.a_o.push(l * r);
+        (
+            Variable::MultiplierLeft(i),
+            Variable::MultiplierRight(i),
+            Variable::MultiplierOutput(i),
+        )
+    }
+}
+
+pub struct Verifier<'a, G: AffineRepr> {
+    transcript: &'a mut Transcript,
+    commitments: Vec<G>,
+    num_multipliers: usize,
+    constraints: Vec<LinearCombination<G::ScalarField>>,
+    pending_multiplier: Option<usize>,
+}
+
+impl<'a, G: DeriveGenerators> Verifier<'a, G> {
+    pub fn new(transcript: &'a mut Transcript) -> Self {
+        transcript.domain_separator(b"r1cs");
+        Self {
+            transcript,
+            commitments: Vec::new(),
+            num_multipliers: 0,
+            constraints: Vec::new(),
+            pending_multiplier: None,
+        }
+    }
+
+    /// Adds the commitment C_v to the circuit, returning the variable of the committed value
+    pub fn commit(&mut self, c_v: G) -> Variable<G::ScalarField> {
+        self.transcript.append_point(b"V", &c_v);
+        self.commitments.push(c_v);
+        Variable::Committed(self.commitments.len() - 1)
+    }
+
+    /// Verifies the proof against the circuit built so far
+    pub fn verify(self, proof: &R1csProof<G>) -> Result<(), ProofError> {
+        let n = self.num_multipliers.next_power_of_two();
+        let gens = RangeProofGenerators::<G>::new(n);
+
+        let transcript = self.transcript;
+        transcript.append_u64(b"m", self.commitments.len() as u64);
+        transcript.append_u64(b"n", n as u64);
+
+        transcript.append_point(b"A_I", &proof.c_ai);
+        transcript.append_point(b"A_O", &proof.c_ao);
+        transcript.append_point(b"S", &proof.c_s);
+        let y: G::ScalarField = transcript.challenge_scalar(b"y");
+        let z: G::ScalarField = transcript.challenge_scalar(b"z");
+
+        let c_t = [proof.c_t1, proof.c_t3, proof.c_t4, proof.c_t5, proof.c_t6];
+        for (label, c_ti) in T_LABELS.iter().zip(c_t.iter()) {
+            transcript.append_point(label, c_ti);
+        }
+        let x: G::ScalarField = transcript.challenge_scalar(b"x");
+
+        let weights = flatten_constraints(&self.constraints, &z, n, self.num_multipliers, self.commitments.len())?;
+        let y_n = powers(&y, n);
+        let y_inv_n = powers(&y.inverse().ok_or(ProofError::ZeroChallenge)?, n);
+        let delta = y_inv_n.checked_mul(&weights.w_r)?.inner_product(&weights.w_l)?;
+
+        // Check 1: G^t(x) + B^τ_x = Σ_j V_j^(x^2*w_V,j) + G^(x^2*(δ(y, z) + w_c)) + Σ_i T_i^(x^i)
+        let lhs = (gens.g * proof.t_x + gens.b * proof.tau_x).into_affine();
+        let rhs = (scale(&weights.w_v, &(x * x)).commit(&self.commitments)?
+            + gens.g * (x * x * (delta + weights.w_c))
+            + [1, 3, 4, 5, 6]
+                .into_iter()
+                .zip(c_t.iter())
+                .map(|(i, c_ti)| *c_ti * x.pow([i as u64]))
+                .sum::<G::Group>())
+        .into_affine();
+        if lhs != rhs {
+            return Err(ProofError::VerificationFailure);
+        }
+
+        // Check 2: P = A_I^x + A_O^(x^2) + S^(x^3) + <x*y^-n ∘ w_R, g> + <w_O - y^n + x*w_L, h'> - B^μ + Q^t(x)
+        //            = <l, g> + <r, h'> + Q^<l, r>
+        let q = inner_product_base(transcript, &proof.t_x, &proof.mu, &proof.tau_x, &gens.g);
+        let h_prime = scaled_h_vec(&gens.h_vec, &y)?;
+        let g_exponents = scale(&y_inv_n.checked_mul(&weights.w_r)?, &x);
+        let h_exponents = weights
+            .w_o
+            .checked_add(&scale(&y_n, &-G::ScalarField::ONE))?
+            .checked_add(&scale(&weights.w_l, &x))?;
+
+        let p = (proof.c_ai * x
+            + proof.c_ao * (x * x)
+            + proof.c_s * (x * x * x)
+            + g_exponents.commit(&gens.g_vec)?
+            + h_exponents.commit(&h_prime)?
+            - gens.b * proof.mu
+            + q * proof.t_x)
+            .into_affine();
+
+        log_ipa_proof::verify(transcript, &gens.g_vec, &h_prime, &q, &p, &proof.ipa_proof)
+    }
+}
+
+impl<G: DeriveGenerators> ConstraintSystem<G::ScalarField> for Verifier<'_, G> {
+    fn transcript(&mut self) -> &mut Transcript {
+        self.transcript
+    }
+
+    fn evaluate(&self, _: &LinearCombination<G::ScalarField>) -> Option<G::ScalarField> {
+        None
+    }
+
+    fn multiply(
+        &mut self,
+        mut left: LinearCombination<G::ScalarField>,
+        mut right: LinearCombination<G::ScalarField>,
+    ) -> MultiplierWires<G::ScalarField> {
+        let (l_var, r_var, o_var) = self.push_multiplier();
+
+        left.terms.push((l_var, -G::ScalarField::ONE));
+        right.terms.push((r_var, -G::ScalarField::ONE));
+        self.constrain(left);
+        self.constrain(right);
+        (l_var, r_var, o_var)
+    }
+
+    fn allocate(&mut self, _: Option<G::ScalarField>) -> Result<Variable<G::ScalarField>, ProofError> {
+        match self.pending_multiplier.take() {
+            None => {
+                let (l_var, _, _) = self.push_multiplier();
+                self.pending_multiplier = Some(self.num_multipliers - 1);
+                Ok(l_var)
+            }
+            Some(i) => Ok(Variable::MultiplierRight(i)),
+        }
+    }
+
+    fn allocate_multiplier(
+        &mut self,
+        _: Option<(G::ScalarField, G::ScalarField)>,
+    ) -> Result<MultiplierWires<G::ScalarField>, ProofError> {
+        Ok(self.push_multiplier())
+    }
+
+    fn constrain(&mut self, lc: LinearCombination<G::ScalarField>) {
+        self.constraints.push(lc);
+    }
+}
+
+impl<G: AffineRepr> Verifier<'_, G> {
+    fn push_multiplier(&mut self) -> MultiplierWires<G::ScalarField> {
+        let i = self.num_multipliers;
+        self.num_multipliers += 1;
+        (
+            Variable::MultiplierLeft(i),
+            Variable::MultiplierRight(i),
+            Variable::MultiplierOutput(i),
+        )
+    }
+}
+
+const T_LABELS: [&[u8]; 5] = [b"T_1", b"T_3", b"T_4", b"T_5", b"T_6"];
+
+/// The constraints combined with the weights z, z^2, ..., z^Q
+struct FlattenedConstraints<F: PrimeField> {
+    w_l: Coeff<F>,
+    w_r: Coeff<F>,
+    w_o: Coeff<F>,
+    w_v: Coeff<F>,
+    w_c: F,
+}
+
+/// Every constraint Σ c_i * variable_i = 0 is weighted by a power of z and split by wire type. Committed values
+/// and constants move to the right hand side of <w_L, a_L> + <w_R, a_R> + <w_O, a_O> = <w_V, v> + w_c. Fails on
+/// variables outside of the num_multipliers gates and m commitments of the circuit, including the padding gates.
+fn flatten_constraints<F: PrimeField>(
+    constraints: &[LinearCombination<F>],
+    z: &F,
+    n: usize,
+    num_multipliers: usize,
+    m: usize,
+) -> Result<FlattenedConstraints<F>, ProofError> {
+    let mut weights = FlattenedConstraints {
+        w_l: Coeff::zero(n),
+        w_r: Coeff::zero(n),
+        w_o: Coeff::zero(n),
+        w_v: Coeff::zero(m),
+        w_c: F::ZERO,
+    };
+
+    let mut z_q = *z;
+    for lc in constraints {
+        for (variable, c) in lc.terms.iter() {
+            let in_range = match variable {
+                Variable::MultiplierLeft(i) | Variable::MultiplierRight(i) | Variable::MultiplierOutput(i) => {
+                    *i < num_multipliers
+                }
+                Variable::Committed(j) => *j < m,
+                Variable::One(_) => true,
+            };
+            if !in_range {
+                return Err(ProofError::UnknownVariable);
+            }
+            match variable {
+                Variable::MultiplierLeft(i) => weights.w_l[*i] += z_q * c,
+                Variable::MultiplierRight(i) => weights.w_r[*i] += z_q * c,
+                Variable::MultiplierOutput(i) => weights.w_o[*i] += z_q * c,
+                Variable::Committed(j) => weights.w_v[*j] -= z_q * c,
+                Variable::One(_) => weights.w_c -= z_q * c,
+            }
+        }
+        z_q *= z;
+    }
+    Ok(weights)
+}
@@ -1,55 +1,57 @@
-use std::error::Error;
-
-use ark_bn254::{Config, Fq, Fr as F, G1Affine};
-use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{Field, PrimeField};
-use ark_poly::{
-    polynomial,
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
 };
-use ark_std::{rand, One};
-use rand::Rng;
+use ark_ff::{Field, PrimeField};
+use rand::{CryptoRng, RngCore};
 use sha256::digest;
 
-fn generate_random_point(seed: String) -> (G1Affine, String) {
+fn generate_random_point<P: SWCurveConfig>(seed: String) -> (Affine<P>, String)
+where
+    P::BaseField: PrimeField,
+{
     let hash = digest(seed.as_bytes());
     let next_hash = digest(hash.as_bytes());
-    let mut x = Fq::from_le_bytes_mod_order(hash.as_bytes());
+    let mut x = P::BaseField::from_le_bytes_mod_order(hash.as_bytes());
     let mut y;
 
     loop {
-        if let Some(y_value) = find_y_for_x(x) {
+        if let Some(y_value) = find_y_for_x::<P>(x) {
             y = y_value;
-            let point = G1Affine::new_unchecked(x, y);
+            let point = Affine::<P>::new_unchecked(x, y);
             if point.is_on_curve() {
-                return (point, next_hash);
+                // Clearing the cofactor maps the point into the prime order subgroup, and is a no-op on curves
+                // of prime order such as BN254
+                return (point.clear_cofactor(), next_hash);
             }
         }
-        x = x + Fq::from(1);
+        x += P::BaseField::from(1);
     }
 }
 
-pub fn generate_n_random_points(seed: String, num_point: i32) -> Vec<G1Affine> {
-    let mut random_points = Vec::<G1Affine>::new();
+/// Derives num_point generators of the curve from the seed by hashing to x coordinates with SHA-256
+pub fn generate_n_random_points<P: SWCurveConfig>(seed: String, num_point: i32) -> Vec<Affine<P>>
+where
+    P::BaseField: PrimeField,
+{
+    let mut random_points = Vec::<Affine<P>>::new();
     let mut current_seed = seed;
     for _ in 0..num_point {
-        let (point, next_seed) = generate_random_point(current_seed);
+        let (point, next_seed) = generate_random_point::<P>(current_seed);
         random_points.push(point);
         current_seed = next_seed.to_string();
     }
     random_points
 }
 
-fn find_y_for_x(x: Fq) -> Option<Fq> {
-    // y^2 = x^3 + 3
+fn find_y_for_x<P: SWCurveConfig>(x: P::BaseField) -> Option<P::BaseField> {
+    // y^2 = x^3 + a*x + b, which is y^2 = x^3 + 3 on BN254
     let x_cubed = x * x * x;
-    let rhs = x_cubed + Fq::from(3);
+    let rhs = x_cubed + P::mul_by_a(x) + P::COEFF_B;
     rhs.sqrt()
 }
 
-pub fn generate_random_field_element() -> F {
-    let mut rng = rand::thread_rng();
-    let num = rng.gen_range(1..100000);
-    F::from(num)
+/// Samples a field element uniformly from the whole scalar field
+pub fn generate_random_field_element<F: PrimeField, R: RngCore + CryptoRng>(rng: &mut R) -> F {
+    F::rand(rng)
 }
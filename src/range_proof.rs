@@ -0,0 +1,397 @@
+use std::ops::Range;
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, Field, PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::{check_length, ProofError},
+    log_ipa_proof::{self, batch_check, InnerProductProof, MsmEquation},
+    pedersen_commitment::DeriveGenerators,
+    transcript::Transcript,
+    vector_polynomial::{Coeff, InnerProduct, VectorPolynomial},
+    zk_ipa::inner_product_base,
+    zk_mul::generate_random_field_element,
+};
+
+/// Bit sizes a range proof can be created for
+pub const SUPPORTED_BIT_SIZES: [usize; 4] = [8, 16, 32, 64];
+
+/// Generators of a range proof: g_vec and h_vec commit to the bit vectors, G to the value and B to the
+/// blinding factor
+#[derive(Clone, Debug)]
+pub struct RangeProofGenerators<G: AffineRepr> {
+    pub g_vec: Vec<G>,
+    pub h_vec: Vec<G>,
+    pub g: G,
+    pub b: G,
+}
+
+impl<G: DeriveGenerators> RangeProofGenerators<G> {
+    /// Derives the generators for vectors of length n. The vectors are derived sequentially from fixed seeds, so
+    /// the generators for n are a prefix of the generators for any larger length.
+    pub fn new(n: usize) -> Self {
+        let (g, b) = G::pedersen_generators();
+        Self {
+            g_vec: G::generate_n_random_points("range_proof_g".to_string(), n as i32),
+            h_vec: G::generate_n_random_points("range_proof_h".to_string(), n as i32),
+            g,
+            b,
+        }
+    }
+}
+
+impl<G: AffineRepr> RangeProofGenerators<G> {
+    /// C_v = G^v + B^γ
+    pub fn commit(&self, v: &G::ScalarField, gamma: &G::ScalarField) -> G {
+        (self.g * *v + self.b * *gamma).into_affine()
+    }
+}
+
+/// Proof that a Pedersen commitment C_v = G^v + B^γ opens to a value in [0, 2^n)
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct RangeProof<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_a: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_s: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t1: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t2: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub tau_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub mu: G::ScalarField,
+    pub ipa_proof: InnerProductProof<G>,
+}
+
+/// Proves that v lies in [0, 2^n_bits), returning the proof and the commitment C_v = G^v + B^γ it is verified against
+pub fn prove_range<G: DeriveGenerators, R: RngCore + CryptoRng>(
+    v: u64,
+    gamma: G::ScalarField,
+    n_bits: usize,
+    rng: &mut R,
+) -> Result<(RangeProof<G>, G), ProofError> {
+    let (proof, commitments) = prove_range_aggregated(&[v], &[gamma], n_bits, rng)?;
+    Ok((proof, commitments[0]))
+}
+
+/// Verifies that the value committed in C_v lies in [0, 2^n_bits)
+pub fn verify_range<G: DeriveGenerators>(c_v: &G, proof: &RangeProof<G>, n_bits: usize) -> Result<(), ProofError> {
+    verify_range_aggregated(&[*c_v], proof, n_bits)
+}
+
+/// Proves that every value lies in [0, 2^n_bits) with a single proof, returning the proof and the commitments
+/// C_j = G^v_j + B^γ_j. The number of values is padded to a power of two with commitments to zero, so the proof
+/// grows by 2 group elements whenever the number of values doubles.
+pub fn prove_range_aggregated<G: DeriveGenerators, R: RngCore + CryptoRng>(
+    values: &[u64],
+    blindings: &[G::ScalarField],
+    n_bits: usize,
+    rng: &mut R,
+) -> Result<(RangeProof<G>, Vec<G>), ProofError> {
+    check_bit_size(n_bits)?;
+    if values.is_empty() {
+        return Err(ProofError::EmptyInput);
+    }
+    check_length(values.len(), blindings.len())?;
+    if n_bits < 64 && values.iter().any(|v| v >> n_bits != 0) {
+        return Err(ProofError::ValueOutOfRange);
+    }
+
+    let m = values.len().next_power_of_two();
+    let nm = n_bits * m;
+    let gens = RangeProofGenerators::<G>::new(nm);
+
+    // Padding values are zero with a zero blinding factor, so their commitments are the point at infinity
+    let num_values = values.len();
+    let mut values = values.to_vec();
+    let mut blindings = blindings.to_vec();
+    values.resize(m, 0);
+    blindings.resize(m, G::ScalarField::ZERO);
+
+    let commitments: Vec<G> = values
+        .iter()
+        .zip(blindings.iter())
+        .map(|(v, gamma)| gens.commit(&G::ScalarField::from(*v), gamma))
+        .collect();
+
+    let mut transcript = range_proof_transcript(n_bits, &commitments);
+
+    // a_L holds the bits of every v_j, so <a_L[j*n..(j+1)*n], 2^n> = v_j, and a_R = a_L - 1 is zero exactly
+    // where a_L is one
+    let a_l = Coeff::new(
+        values
+            .iter()
+            .flat_map(|v| (0..n_bits).map(move |i| G::ScalarField::from((v >> i) & 1)))
+            .collect(),
+    );
+    let a_r = a_l.checked_add(&Coeff::new(vec![-G::ScalarField::ONE; nm]))?;
+
+    let alpha: G::ScalarField = generate_random_field_element(rng);
+    // A = <a_L, g> + <a_R, h> + B^α
+    let c_a = (a_l.commit(&gens.g_vec)? + a_r.commit(&gens.h_vec)? + gens.b * alpha).into_affine();
+
+    let s_l = Coeff::random(nm, rng);
+    let s_r = Coeff::random(nm, rng);
+    let rho: G::ScalarField = generate_random_field_element(rng);
+    // S = <s_L, g> + <s_R, h> + B^ρ
+    let c_s = (s_l.commit(&gens.g_vec)? + s_r.commit(&gens.h_vec)? + gens.b * rho).into_affine();
+
+    transcript.append_point(b"A", &c_a);
+    transcript.append_point(b"S", &c_s);
+    let y: G::ScalarField = transcript.challenge_scalar(b"y");
+    let z: G::ScalarField = transcript.challenge_scalar(b"z");
+
+    let y_nm = powers(&y, nm);
+
+    // l(X) = (a_L - z*1) + s_L*X
+    let l_x = VectorPolynomial::new(vec![a_l.checked_add(&Coeff::new(vec![-z; nm]))?, s_l])?;
+    // r(X) = y^nm ∘ (a_R + z*1 + s_R*X) + Σ_j z^(2+j) * (0^(j*n) || 2^n || 0^((m-j-1)*n))
+    let r_x = VectorPolynomial::new(vec![
+        y_nm.checked_mul(&a_r.checked_add(&Coeff::new(vec![z; nm]))?)?.checked_add(&z_two_n(&z, n_bits, 0..m))?,
+        y_nm.checked_mul(&s_r)?,
+    ])?;
+
+    // t(X) = <l(X), r(X)> = t_0 + t_1*X + t_2*X^2, where t_0 = Σ_j z^(2+j)*v_j + δ(y, z) whenever every v_j is in range
+    let t_poly = l_x.checked_mul(&r_x)?;
+    let t_1 = t_poly.coeffs.get(1).copied().unwrap_or(G::ScalarField::ZERO);
+    let t_2 = t_poly.coeffs.get(2).copied().unwrap_or(G::ScalarField::ZERO);
+
+    let tau_1: G::ScalarField = generate_random_field_element(rng);
+    let tau_2: G::ScalarField = generate_random_field_element(rng);
+    // T_i = G^t_i + B^τ_i
+    let c_t1 = (gens.g * t_1 + gens.b * tau_1).into_affine();
+    let c_t2 = (gens.g * t_2 + gens.b * tau_2).into_affine();
+
+    transcript.append_point(b"T_1", &c_t1);
+    transcript.append_point(b"T_2", &c_t2);
+    let x: G::ScalarField = transcript.challenge_scalar(b"x");
+
+    let l = l_x.evaluate(&x);
+    let r = r_x.evaluate(&x);
+    let t_x = l.inner_product(&r)?;
+    // τ_x = τ_2*x^2 + τ_1*x + Σ_j z^(2+j)*γ_j blinds t(x), μ = α + ρ*x blinds l(x) and r(x)
+    let tau_x = tau_2 * x * x + tau_1 * x + powers(&z, m).inner_product(&Coeff::new(blindings))? * z * z;
+    let mu = alpha + rho * x;
+
+    let q = inner_product_base(&mut transcript, &t_x, &mu, &tau_x, &gens.g);
+    let h_prime = scaled_h_vec(&gens.h_vec, &y)?;
+    let ipa_proof = log_ipa_proof::prove_bound(&mut transcript, &gens.g_vec, &h_prime, &q, &l.0, &r.0)?;
+
+    let proof = RangeProof {
+        c_a,
+        c_s,
+        c_t1,
+        c_t2,
+        t_x,
+        tau_x,
+        mu,
+        ipa_proof,
+    };
+    Ok((proof, commitments[..num_values].to_vec()))
+}
+
+/// Verifies that every value committed in commitments lies in [0, 2^n_bits)
+pub fn verify_range_aggregated<G: DeriveGenerators>(
+    commitments: &[G],
+    proof: &RangeProof<G>,
+    n_bits: usize,
+) -> Result<(), ProofError> {
+    check_bit_size(n_bits)?;
+    let gens = RangeProofGenerators::<G>::new(n_bits * commitments.len().next_power_of_two());
+    let (value_equation, ipa_equation) = verification_equations(&gens, commitments, proof, n_bits)?;
+    value_equation.check()?;
+    ipa_equation.check()
+}
+
+/// Verifies many range proofs, each given with its value commitments, in one multi-scalar multiplication. The
+/// generators are derived once, for the largest aggregation in the batch, and every proof uses a prefix of them, so
+/// the g_vec and h_vec terms of all proofs fall onto the same bases. The cost grows with the group elements of the
+/// proofs plus the generators of the largest proof, rather than with the number of proofs times the size of the
+/// generators. The weights of the batch are sampled from rng. If the batch is rejected, the indices of the failing
+/// proofs are returned in ProofError::BatchVerificationFailure.
+pub fn batch_verify<G: DeriveGenerators, R: RngCore + CryptoRng>(
+    proofs: &[(&[G], &RangeProof<G>)],
+    n_bits: usize,
+    rng: &mut R,
+) -> Result<(), ProofError> {
+    let m_max = proofs
+        .iter()
+        .map(|(commitments, _)| commitments.len().next_power_of_two())
+        .max()
+        .unwrap_or(1);
+    // Proofs for an unsupported bit size are rejected without deriving any generators
+    let nm_max = if check_bit_size(n_bits).is_ok() { n_bits * m_max } else { 0 };
+    let gens = RangeProofGenerators::<G>::new(nm_max);
+
+    let equations = proofs
+        .iter()
+        .map(|(commitments, proof)| verification_equation(&gens, commitments, proof, n_bits, rng))
+        .collect();
+    batch_check(equations, rng)
+}
+
+/// Replays the verifier of a range proof and returns both of its checks combined into one equation with a weight
+/// sampled from rng
+pub(crate) fn verification_equation<G: AffineRepr, R: RngCore + CryptoRng>(
+    gens: &RangeProofGenerators<G>,
+    commitments: &[G],
+    proof: &RangeProof<G>,
+    n_bits: usize,
+    rng: &mut R,
+) -> Result<MsmEquation<G>, ProofError> {
+    let (mut equation, ipa_equation) = verification_equations(gens, commitments, proof, n_bits)?;
+    equation.combine(&ipa_equation, G::ScalarField::rand(rng));
+    Ok(equation)
+}
+
+/// Replays the verifier of a range proof and returns its two checks: the one on the committed values and t(x), and
+/// the inner product argument. gens has to hold at least n_bits * m vector generators, m being the number of
+/// commitments padded to a power of two.
+pub(crate) fn verification_equations<G: AffineRepr>(
+    gens: &RangeProofGenerators<G>,
+    commitments: &[G],
+    proof: &RangeProof<G>,
+    n_bits: usize,
+) -> Result<(MsmEquation<G>, MsmEquation<G>), ProofError> {
+    check_bit_size(n_bits)?;
+    if commitments.is_empty() {
+        return Err(ProofError::EmptyInput);
+    }
+
+    let m = commitments.len().next_power_of_two();
+    let nm = n_bits * m;
+    if gens.g_vec.len() < nm || gens.h_vec.len() < nm {
+        return Err(ProofError::LengthMismatch { expected: nm, found: gens.g_vec.len().min(gens.h_vec.len()) });
+    }
+    let (g_vec, h_vec) = (&gens.g_vec[..nm], &gens.h_vec[..nm]);
+
+    let mut commitments = commitments.to_vec();
+    commitments.resize(m, G::zero());
+
+    let mut transcript = range_proof_transcript(n_bits, &commitments);
+    transcript.append_point(b"A", &proof.c_a);
+    transcript.append_point(b"S", &proof.c_s);
+    let y: G::ScalarField = transcript.challenge_scalar(b"y");
+    let z: G::ScalarField = transcript.challenge_scalar(b"z");
+    transcript.append_point(b"T_1", &proof.c_t1);
+    transcript.append_point(b"T_2", &proof.c_t2);
+    let x: G::ScalarField = transcript.challenge_scalar(b"x");
+
+    let z_m = powers(&z, m);
+
+    // Check 1: G^t(x) + B^τ_x = Σ_j V_j^(z^(2+j)) + G^δ(y, z) + T_1^x + T_2^(x^2)
+    let mut equation = MsmEquation::new();
+    equation.push(gens.g, proof.t_x - delta(&y, &z, n_bits, 0..m));
+    equation.push(gens.b, proof.tau_x);
+    equation.extend(&commitments, scale(&z_m, &-(z * z)).0);
+    equation.push(proof.c_t1, -x);
+    equation.push(proof.c_t2, -(x * x));
+
+    // Check 2: the inner product argument for P = A + S^x - <z*1, g> + <z*y^nm + Σ_j z^(2+j)*2^n, h'> - B^μ + Q^t(x),
+    // where h'_i = h_i^(y^-i). P is expanded into its terms and y^-i goes into the scalars of h rather than the bases:
+    //   <a*s + z*1, g> + <y^-nm ∘ (b*s^-1 - Σ_j z^(2+j)*2^n) - z*1, h> + Q^(a*b - t(x)) + B^μ - A - S^x
+    //   - Σ_k (L_k^(u_k^2) + R_k^(u_k^-2)) = 0
+    let q = inner_product_base(&mut transcript, &proof.t_x, &proof.mu, &proof.tau_x, &gens.g);
+    let ipa_proof = &proof.ipa_proof;
+    let (challenges, challenges_inv, s) = log_ipa_proof::verification_scalars_bound(&mut transcript, nm, ipa_proof)?;
+    let y_inv_nm = powers(&y.inverse().ok_or(ProofError::ZeroChallenge)?, nm);
+    let (a, b) = (ipa_proof.a, ipa_proof.b);
+
+    let mut ipa_equation = MsmEquation::new();
+    ipa_equation.extend(g_vec, s.iter().map(|s_i| a * s_i + z));
+    ipa_equation.extend(
+        h_vec,
+        s.iter()
+            .rev()
+            .zip(y_inv_nm.0.iter())
+            .zip(z_two_n(&z, n_bits, 0..m).0)
+            .map(|((s_i_inv, y_inv_i), z_two_n_i)| (b * s_i_inv - z_two_n_i) * y_inv_i - z),
+    );
+    ipa_equation.push(q, a * b - proof.t_x);
+    ipa_equation.push(gens.b, proof.mu);
+    ipa_equation.push(proof.c_a, -G::ScalarField::ONE);
+    ipa_equation.push(proof.c_s, -x);
+    for ((l_com, r_com), (u, u_inv)) in ipa_proof
+        .l_vec
+        .iter()
+        .zip(ipa_proof.r_vec.iter())
+        .zip(challenges.iter().zip(challenges_inv.iter()))
+    {
+        ipa_equation.push(*l_com, -(*u * u));
+        ipa_equation.push(*r_com, -(*u_inv * u_inv));
+    }
+
+    Ok((equation, ipa_equation))
+}
+
+pub(crate) fn check_bit_size(n_bits: usize) -> Result<(), ProofError> {
+    if !SUPPORTED_BIT_SIZES.contains(&n_bits) {
+        return Err(ProofError::UnsupportedBitSize(n_bits));
+    }
+    Ok(())
+}
+
+/// Starts the transcript of a range proof, bound to the bit size and every (padded) value commitment
+pub(crate) fn range_proof_transcript<G: AffineRepr>(n_bits: usize, commitments: &[G]) -> Transcript {
+    let mut transcript = Transcript::new(b"range_proof");
+    transcript.append_u64(b"n", n_bits as u64);
+    transcript.append_u64(b"m", commitments.len() as u64);
+    for commitment in commitments {
+        transcript.append_point(b"V", commitment);
+    }
+    transcript
+}
+
+/// δ(y, z) = Σ_j ((z - z^2)*<1, y^n_j> - z^(3+j)*<1, 2^n>) over the positions j, where y^n_j is the slice
+/// y^(j*n..(j+1)*n) of y^nm. Over 0..m this is (z - z^2)*<1, y^nm> - Σ_j z^(3+j)*<1, 2^n>.
+pub(crate) fn delta<F: PrimeField>(y: &F, z: &F, n_bits: usize, positions: Range<usize>) -> F {
+    let sum_y: F = powers(y, n_bits).0.iter().sum();
+    let sum_two: F = powers(&F::from(2), n_bits).0.iter().sum();
+    positions
+        .map(|j| {
+            let y_jn = y.pow([(j * n_bits) as u64]);
+            let z_j = z.pow([j as u64]);
+            (*z - *z * z) * y_jn * sum_y - z_j * z * z * z * sum_two
+        })
+        .sum()
+}
+
+/// The concatenation of z^(2+j)*2^n over the positions j
+pub(crate) fn z_two_n<F: PrimeField>(z: &F, n_bits: usize, positions: Range<usize>) -> Coeff<F> {
+    let two_n = powers(&F::from(2), n_bits);
+    Coeff::new(
+        positions
+            .flat_map(|j| scale(&two_n, &z.pow([(2 + j) as u64])).0)
+            .collect(),
+    )
+}
+
+/// Returns (1, x, x^2, ..., x^(n-1))
+pub(crate) fn powers<F: PrimeField>(x: &F, n: usize) -> Coeff<F> {
+    let mut result = Vec::with_capacity(n);
+    let mut current = F::ONE;
+    for _ in 0..n {
+        result.push(current);
+        current *= x;
+    }
+    Coeff::new(result)
+}
+
+pub(crate) fn scale<F: PrimeField>(vector: &Coeff<F>, scalar: &F) -> Coeff<F> {
+    Coeff::new(vector.0.iter().map(|value| *value * scalar).collect())
+}
+
+/// h'_i = h_i^(y^-i), which turns the y^n ∘ r(X) weighting into a plain inner product
+pub(crate) fn scaled_h_vec<G: AffineRepr>(h_vec: &[G], y: &G::ScalarField) -> Result<Vec<G>, ProofError> {
+    let y_inv_n = powers(&y.inverse().ok_or(ProofError::ZeroChallenge)?, h_vec.len());
+    let h_prime: Vec<G::Group> = h_vec.iter().zip(y_inv_n.0.iter()).map(|(h, y_inv_i)| *h * y_inv_i).collect();
+    Ok(G::Group::normalize_batch(&h_prime))
+}
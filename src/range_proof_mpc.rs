@@ -0,0 +1,537 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, Field};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::{check_length, ProofError},
+    log_ipa_proof,
+    pedersen_commitment::DeriveGenerators,
+    range_proof::{
+        check_bit_size, delta, powers, range_proof_transcript, scale, scaled_h_vec, verification_equations, z_two_n,
+        RangeProof, RangeProofGenerators,
+    },
+    transcript::Transcript,
+    vector_polynomial::{Coeff, InnerProduct, VectorPolynomial},
+    zk_ipa::inner_product_base,
+    zk_mul::generate_random_field_element,
+};
+
+// Multi-party computation of an aggregated range proof. Every party holds one secret value and its
+// blinding factor and only ever sends commitments and blinded evaluations to the dealer, who combines them
+// into one RangeProof that verify_range_aggregated accepts. Each stage of the protocol is a separate type,
+// so a party or dealer can only process the messages in order.
+
+/// Commitments of party j to its value and bit vectors: V_j, A_j and S_j
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BitCommitment<G: AffineRepr> {
+    pub position: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub v_j: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub a_j: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub s_j: G,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BitChallenge<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub y: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub z: G::ScalarField,
+}
+
+/// Commitments of party j to the coefficients t_1 and t_2 of its share of t(X)
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct PolyCommitment<G: AffineRepr> {
+    pub position: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t1_j: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t2_j: G,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct PolyChallenge<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub x: G::ScalarField,
+}
+
+/// The share of party j of l(x), r(x) and their blinding factors
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ProofShare<G: AffineRepr> {
+    pub position: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub tau_x: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub mu: G::ScalarField,
+    pub l_vec: Coeff<G::ScalarField>,
+    pub r_vec: Coeff<G::ScalarField>,
+}
+
+pub struct Party;
+
+impl Party {
+    /// Starts a party that proves v lies in [0, 2^n_bits) for the commitment G^v + B^γ, as one of the m parties
+    /// the dealer aggregates
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<G: DeriveGenerators>(
+        v: u64,
+        gamma: G::ScalarField,
+        n_bits: usize,
+        m: usize,
+    ) -> Result<PartyAwaitingPosition<G>, ProofError> {
+        check_bit_size(n_bits)?;
+        if m == 0 {
+            return Err(ProofError::EmptyInput);
+        }
+        if n_bits < 64 && v >> n_bits != 0 {
+            return Err(ProofError::ValueOutOfRange);
+        }
+        Ok(PartyAwaitingPosition { n_bits, m, v, gamma })
+    }
+}
+
+pub struct PartyAwaitingPosition<G: AffineRepr> {
+    n_bits: usize,
+    m: usize,
+    v: u64,
+    gamma: G::ScalarField,
+}
+
+impl<G: DeriveGenerators> PartyAwaitingPosition<G> {
+    /// Commits to the bits of v using the generators of position j, g_vec[j*n..(j+1)*n] and h_vec[j*n..(j+1)*n].
+    /// The position comes from the dealer, so one outside of the m agreed parties is rejected before any
+    /// generators are derived.
+    pub fn assign_position<R: RngCore + CryptoRng>(
+        self,
+        position: usize,
+        rng: &mut R,
+    ) -> Result<(PartyAwaitingBitChallenge<G>, BitCommitment<G>), ProofError> {
+        let n = self.n_bits;
+        let out_of_range = ProofError::PositionOutOfRange { position, m: self.m };
+        if position >= self.m {
+            return Err(out_of_range);
+        }
+        let start = position.checked_mul(n).ok_or(out_of_range.clone())?;
+        let end = start.checked_add(n).ok_or(out_of_range)?;
+        let gens = RangeProofGenerators::<G>::new(end);
+        let g_vec = gens.g_vec[start..].to_vec();
+        let h_vec = gens.h_vec[start..].to_vec();
+
+        let v_j = gens.commit(&G::ScalarField::from(self.v), &self.gamma);
+
+        let a_l = Coeff::new((0..n).map(|i| G::ScalarField::from((self.v >> i) & 1)).collect());
+        let a_r = a_l.checked_add(&Coeff::new(vec![-G::ScalarField::ONE; n]))?;
+
+        let alpha: G::ScalarField = generate_random_field_element(rng);
+        // A_j = <a_L, g_j> + <a_R, h_j> + B^α_j
+        let a_j = (a_l.commit(&g_vec)? + a_r.commit(&h_vec)? + gens.b * alpha).into_affine();
+
+        let s_l = Coeff::random(n, rng);
+        let s_r = Coeff::random(n, rng);
+        let rho: G::ScalarField = generate_random_field_element(rng);
+        // S_j = <s_L, g_j> + <s_R, h_j> + B^ρ_j
+        let s_j = (s_l.commit(&g_vec)? + s_r.commit(&h_vec)? + gens.b * rho).into_affine();
+
+        let bit_commitment = BitCommitment {
+            position,
+            v_j,
+            a_j,
+            s_j,
+        };
+        let next_state = PartyAwaitingBitChallenge {
+            n_bits: n,
+            position,
+            gamma: self.gamma,
+            gens,
+            a_l,
+            a_r,
+            alpha,
+            s_l,
+            s_r,
+            rho,
+        };
+        Ok((next_state, bit_commitment))
+    }
+}
+
+pub struct PartyAwaitingBitChallenge<G: AffineRepr> {
+    n_bits: usize,
+    position: usize,
+    gamma: G::ScalarField,
+    gens: RangeProofGenerators<G>,
+    a_l: Coeff<G::ScalarField>,
+    a_r: Coeff<G::ScalarField>,
+    alpha: G::ScalarField,
+    s_l: Coeff<G::ScalarField>,
+    s_r: Coeff<G::ScalarField>,
+    rho: G::ScalarField,
+}
+
+impl<G: DeriveGenerators> PartyAwaitingBitChallenge<G> {
+    /// Builds this party's slice of l(X) and r(X) and commits to the coefficients of <l_j(X), r_j(X)>
+    pub fn apply_challenge<R: RngCore + CryptoRng>(
+        self,
+        challenge: &BitChallenge<G>,
+        rng: &mut R,
+    ) -> Result<(PartyAwaitingPolyChallenge<G>, PolyCommitment<G>), ProofError> {
+        let n = self.n_bits;
+        let j = self.position;
+        let BitChallenge { y, z } = challenge;
+
+        // The slice of y^nm that belongs to position j is y^(j*n) * y^n, and the value is weighted by z^(2+j)
+        let y_n_j = scale(&powers(y, n), &y.pow([(j * n) as u64]));
+        let z_j = z.pow([(2 + j) as u64]);
+
+        // l_j(X) = (a_L - z*1) + s_L*X
+        let l_x = VectorPolynomial::new(vec![self.a_l.checked_add(&Coeff::new(vec![-*z; n]))?, self.s_l])?;
+        // r_j(X) = y^n_j ∘ (a_R + z*1 + s_R*X) + z^(2+j)*2^n
+        let r_x = VectorPolynomial::new(vec![
+            y_n_j
+                .checked_mul(&self.a_r.checked_add(&Coeff::new(vec![*z; n]))?)?
+                .checked_add(&z_two_n(z, n, j..j + 1))?,
+            y_n_j.checked_mul(&self.s_r)?,
+        ])?;
+
+        let t_poly = l_x.checked_mul(&r_x)?;
+        let t_1 = t_poly.coeffs.get(1).copied().unwrap_or(G::ScalarField::ZERO);
+        let t_2 = t_poly.coeffs.get(2).copied().unwrap_or(G::ScalarField::ZERO);
+
+        let tau_1: G::ScalarField = generate_random_field_element(rng);
+        let tau_2: G::ScalarField = generate_random_field_element(rng);
+        let t1_j = (self.gens.g * t_1 + self.gens.b * tau_1).into_affine();
+        let t2_j = (self.gens.g * t_2 + self.gens.b * tau_2).into_affine();
+
+        let poly_commitment = PolyCommitment { position: j, t1_j, t2_j };
+        let next_state = PartyAwaitingPolyChallenge {
+            position: j,
+            z_j,
+            gamma: self.gamma,
+            alpha: self.alpha,
+            rho: self.rho,
+            l_x,
+            r_x,
+            tau_1,
+            tau_2,
+        };
+        Ok((next_state, poly_commitment))
+    }
+}
+
+pub struct PartyAwaitingPolyChallenge<G: AffineRepr> {
+    position: usize,
+    z_j: G::ScalarField,
+    gamma: G::ScalarField,
+    alpha: G::ScalarField,
+    rho: G::ScalarField,
+    l_x: VectorPolynomial<G::ScalarField>,
+    r_x: VectorPolynomial<G::ScalarField>,
+    tau_1: G::ScalarField,
+    tau_2: G::ScalarField,
+}
+
+impl<G: DeriveGenerators> PartyAwaitingPolyChallenge<G> {
+    /// Evaluates this party's polynomials at x. A zero x would reveal l_j(0) = a_L - z*1 and therefore the
+    /// secret bits, so it is rejected.
+    pub fn apply_challenge(self, challenge: &PolyChallenge<G>) -> Result<ProofShare<G>, ProofError> {
+        let x = challenge.x;
+        if x == G::ScalarField::ZERO {
+            return Err(ProofError::ZeroChallenge);
+        }
+
+        let l_vec = self.l_x.evaluate(&x);
+        let r_vec = self.r_x.evaluate(&x);
+
+        Ok(ProofShare {
+            position: self.position,
+            t_x: l_vec.inner_product(&r_vec)?,
+            tau_x: self.tau_2 * x * x + self.tau_1 * x + self.z_j * self.gamma,
+            mu: self.alpha + self.rho * x,
+            l_vec,
+            r_vec,
+        })
+    }
+}
+
+pub struct Dealer;
+
+impl Dealer {
+    /// Starts a dealer aggregating the proofs of m parties over n_bits each. When m is not a power of two the
+    /// dealer runs the padding parties for the values zero itself.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<G: DeriveGenerators>(n_bits: usize, m: usize) -> Result<DealerAwaitingBitCommitments<G>, ProofError> {
+        check_bit_size(n_bits)?;
+        if m == 0 {
+            return Err(ProofError::EmptyInput);
+        }
+        let padding = (m..m.next_power_of_two())
+            .map(|_| Party::new::<G>(0, G::ScalarField::ZERO, n_bits, m.next_power_of_two()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(DealerAwaitingBitCommitments { n_bits, m, padding })
+    }
+}
+
+pub struct DealerAwaitingBitCommitments<G: AffineRepr> {
+    n_bits: usize,
+    m: usize,
+    padding: Vec<PartyAwaitingPosition<G>>,
+}
+
+impl<G: DeriveGenerators> DealerAwaitingBitCommitments<G> {
+    /// Also commits for the padding parties, whose blinding factors are sampled from rng
+    pub fn receive_bit_commitments<R: RngCore + CryptoRng>(
+        self,
+        bit_commitments: Vec<BitCommitment<G>>,
+        rng: &mut R,
+    ) -> Result<(DealerAwaitingPolyCommitments<G>, BitChallenge<G>), ProofError> {
+        check_positions(bit_commitments.iter().map(|c| c.position), self.m)?;
+
+        let mut bit_commitments = bit_commitments;
+        let mut padding = Vec::new();
+        for (offset, party) in self.padding.into_iter().enumerate() {
+            let (party, bit_commitment) = party.assign_position(self.m + offset, rng)?;
+            bit_commitments.push(bit_commitment);
+            padding.push(party);
+        }
+
+        let commitments: Vec<G> = bit_commitments.iter().map(|c| c.v_j).collect();
+        let mut transcript = range_proof_transcript(self.n_bits, &commitments);
+
+        // A = Σ_j A_j, S = Σ_j S_j
+        let c_a = sum(bit_commitments.iter().map(|c| c.a_j));
+        let c_s = sum(bit_commitments.iter().map(|c| c.s_j));
+        transcript.append_point(b"A", &c_a);
+        transcript.append_point(b"S", &c_s);
+        let challenge = BitChallenge {
+            y: transcript.challenge_scalar(b"y"),
+            z: transcript.challenge_scalar(b"z"),
+        };
+
+        let next_state = DealerAwaitingPolyCommitments {
+            n_bits: self.n_bits,
+            m: self.m,
+            transcript,
+            bit_commitments,
+            bit_challenge: challenge.clone(),
+            padding,
+            c_a,
+            c_s,
+        };
+        Ok((next_state, challenge))
+    }
+}
+
+pub struct DealerAwaitingPolyCommitments<G: AffineRepr> {
+    n_bits: usize,
+    m: usize,
+    transcript: Transcript,
+    bit_commitments: Vec<BitCommitment<G>>,
+    bit_challenge: BitChallenge<G>,
+    padding: Vec<PartyAwaitingBitChallenge<G>>,
+    c_a: G,
+    c_s: G,
+}
+
+impl<G: DeriveGenerators> DealerAwaitingPolyCommitments<G> {
+    pub fn receive_poly_commitments<R: RngCore + CryptoRng>(
+        self,
+        poly_commitments: Vec<PolyCommitment<G>>,
+        rng: &mut R,
+    ) -> Result<(DealerAwaitingProofShares<G>, PolyChallenge<G>), ProofError> {
+        check_positions(poly_commitments.iter().map(|c| c.position), self.m)?;
+
+        let mut poly_commitments = poly_commitments;
+        let mut padding = Vec::new();
+        for party in self.padding {
+            let (party, poly_commitment) = party.apply_challenge(&self.bit_challenge, rng)?;
+            poly_commitments.push(poly_commitment);
+            padding.push(party);
+        }
+
+        // T_1 = Σ_j T_1,j, T_2 = Σ_j T_2,j
+        let c_t1 = sum(poly_commitments.iter().map(|c| c.t1_j));
+        let c_t2 = sum(poly_commitments.iter().map(|c| c.t2_j));
+
+        let mut transcript = self.transcript;
+        transcript.append_point(b"T_1", &c_t1);
+        transcript.append_point(b"T_2", &c_t2);
+        let challenge = PolyChallenge {
+            x: transcript.challenge_scalar(b"x"),
+        };
+
+        let next_state = DealerAwaitingProofShares {
+            n_bits: self.n_bits,
+            m: self.m,
+            transcript,
+            bit_commitments: self.bit_commitments,
+            bit_challenge: self.bit_challenge,
+            poly_commitments,
+            poly_challenge: challenge.clone(),
+            padding,
+            c_a: self.c_a,
+            c_s: self.c_s,
+            c_t1,
+            c_t2,
+        };
+        Ok((next_state, challenge))
+    }
+}
+
+pub struct DealerAwaitingProofShares<G: AffineRepr> {
+    n_bits: usize,
+    m: usize,
+    transcript: Transcript,
+    bit_commitments: Vec<BitCommitment<G>>,
+    bit_challenge: BitChallenge<G>,
+    poly_commitments: Vec<PolyCommitment<G>>,
+    poly_challenge: PolyChallenge<G>,
+    padding: Vec<PartyAwaitingPolyChallenge<G>>,
+    c_a: G,
+    c_s: G,
+    c_t1: G,
+    c_t2: G,
+}
+
+impl<G: DeriveGenerators> DealerAwaitingProofShares<G> {
+    /// Checks every share against the commitments of its party and combines the shares into the aggregated
+    /// proof, returned together with the value commitments of the m parties
+    pub fn receive_shares(
+        mut self,
+        proof_shares: Vec<ProofShare<G>>,
+    ) -> Result<(RangeProof<G>, Vec<G>), ProofError> {
+        check_positions(proof_shares.iter().map(|s| s.position), self.m)?;
+
+        let mut proof_shares = proof_shares;
+        for party in std::mem::take(&mut self.padding) {
+            proof_shares.push(party.apply_challenge(&self.poly_challenge)?);
+        }
+
+        let n = self.n_bits;
+        let nm = n * proof_shares.len();
+        for share in proof_shares.iter() {
+            check_length(n, share.l_vec.len())?;
+            check_length(n, share.r_vec.len())?;
+        }
+        let gens = RangeProofGenerators::<G>::new(nm);
+        let h_prime = scaled_h_vec(&gens.h_vec, &self.bit_challenge.y)?;
+
+        let t_x: G::ScalarField = proof_shares.iter().map(|s| s.t_x).sum();
+        let tau_x: G::ScalarField = proof_shares.iter().map(|s| s.tau_x).sum();
+        let mu: G::ScalarField = proof_shares.iter().map(|s| s.mu).sum();
+        let l: Vec<G::ScalarField> = proof_shares.iter().flat_map(|s| s.l_vec.0.clone()).collect();
+        let r: Vec<G::ScalarField> = proof_shares.iter().flat_map(|s| s.r_vec.0.clone()).collect();
+
+        let mut transcript = self.transcript.clone();
+        let q = inner_product_base(&mut transcript, &t_x, &mu, &tau_x, &gens.g);
+        let ipa_proof = log_ipa_proof::prove_bound(&mut transcript, &gens.g_vec, &h_prime, &q, &l, &r)?;
+
+        let proof = RangeProof {
+            c_a: self.c_a,
+            c_s: self.c_s,
+            c_t1: self.c_t1,
+            c_t2: self.c_t2,
+            t_x,
+            tau_x,
+            mu,
+            ipa_proof,
+        };
+        let commitments: Vec<G> = self.bit_commitments[..self.m].iter().map(|c| c.v_j).collect();
+
+        // The combined proof is checked like any other range proof, and only a rejected one is audited share by
+        // share to find the parties responsible
+        let (value_equation, ipa_equation) = verification_equations(&gens, &commitments, &proof, n)?;
+        if value_equation.check().and(ipa_equation.check()).is_err() {
+            let bad_shares: Vec<usize> = proof_shares
+                .iter()
+                .filter(|share| self.audit_share(share, &gens, &h_prime).is_err())
+                .map(|share| share.position)
+                .collect();
+            return Err(match bad_shares.is_empty() {
+                true => ProofError::VerificationFailure,
+                false => ProofError::MalformedShares(bad_shares),
+            });
+        }
+        Ok((proof, commitments))
+    }
+
+    /// Checks the share of party j with the equations of the range proof restricted to position j
+    fn audit_share(
+        &self,
+        share: &ProofShare<G>,
+        gens: &RangeProofGenerators<G>,
+        h_prime: &[G],
+    ) -> Result<(), ProofError> {
+        let n = self.n_bits;
+        let j = share.position;
+        if share.t_x != share.l_vec.inner_product(&share.r_vec)? {
+            return Err(ProofError::VerificationFailure);
+        }
+
+        let BitChallenge { y, z } = &self.bit_challenge;
+        let x = self.poly_challenge.x;
+        let bit_commitment = &self.bit_commitments[j];
+        let poly_commitment = &self.poly_commitments[j];
+
+        let y_n_j = scale(&powers(y, n), &y.pow([(j * n) as u64]));
+        let z_j = z.pow([(2 + j) as u64]);
+
+        // G^t_j(x) + B^τ_x,j = V_j^(z^(2+j)) + G^δ_j(y, z) + T_1,j^x + T_2,j^(x^2)
+        let lhs = (gens.g * share.t_x + gens.b * share.tau_x).into_affine();
+        let rhs = (bit_commitment.v_j * z_j
+            + gens.g * delta(y, z, n, j..j + 1)
+            + poly_commitment.t1_j * x
+            + poly_commitment.t2_j * (x * x))
+            .into_affine();
+        if lhs != rhs {
+            return Err(ProofError::VerificationFailure);
+        }
+
+        // <l_j, g_j> + <r_j, h'_j> = A_j + S_j^x - <z*1, g_j> + <z*y^n_j + z^(2+j)*2^n, h'_j> - B^μ_j
+        let g_j = &gens.g_vec[j * n..(j + 1) * n];
+        let h_prime_j = &h_prime[j * n..(j + 1) * n];
+        let h_exponents = scale(&y_n_j, z).checked_add(&z_two_n(z, n, j..j + 1))?;
+
+        let lhs = (share.l_vec.commit(g_j)? + share.r_vec.commit(h_prime_j)?).into_affine();
+        let rhs = (bit_commitment.a_j + bit_commitment.s_j * x - Coeff::new(vec![*z; n]).commit(g_j)?
+            + h_exponents.commit(h_prime_j)?
+            - gens.b * share.mu)
+            .into_affine();
+        if lhs != rhs {
+            return Err(ProofError::VerificationFailure);
+        }
+        Ok(())
+    }
+}
+
+/// Messages have to arrive ordered by position, one from each of the m parties
+fn check_positions(positions: impl Iterator<Item = usize>, m: usize) -> Result<(), ProofError> {
+    let positions: Vec<usize> = positions.collect();
+    check_length(m, positions.len())?;
+    if let Some((expected, position)) = positions.iter().enumerate().find(|(expected, position)| *expected != **position) {
+        return Err(ProofError::UnexpectedPosition {
+            expected,
+            found: *position,
+        });
+    }
+    Ok(())
+}
+
+fn sum<G: AffineRepr>(points: impl Iterator<Item = G>) -> G {
+    points
+        .fold(G::zero().into_group(), |acc, point| acc + point)
+        .into_affine()
+}
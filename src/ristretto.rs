@@ -0,0 +1,189 @@
+use ark_curve25519::{EdwardsAffine, EdwardsProjective, Fq, Fr};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, BigInteger, Field, MontFp, PrimeField};
+
+use crate::{error::ProofError, pedersen_commitment::DeriveGenerators};
+
+/// An element of ristretto255 (RFC 9496), represented by its point in the prime order subgroup of curve25519. Keeping
+/// the twisted Edwards model of ark-curve25519 lets commit, the inner product argument and zk_ipa run on it unchanged,
+/// while compress, decompress and from_uniform_bytes exchange elements with other ristretto255 implementations.
+/// Decoded points are mapped back into the prime order subgroup, so no small order component reaches a proof. The
+/// maps act on public points and encodings and are not constant time.
+pub type RistrettoPoint = EdwardsAffine;
+
+/// The scalar field of ristretto255, of prime order 2^252 + 27742317777372353535851937790883648493
+pub type Scalar = Fr;
+
+// The constants of RFC 9496 for edwards25519, -x² + y² = 1 + d·x²·y²
+const SQRT_M1: Fq = MontFp!("19681161376707505956807079304988542015446066515923890162744021073123829784752");
+const EDWARDS_D: Fq = MontFp!("37095705934669439343138083508754565189542113879843219016388785533085940283555");
+const INVSQRT_A_MINUS_D: Fq =
+    MontFp!("54469307008909316920995813868745141605393597292927456921205312896311721017578");
+const SQRT_AD_MINUS_ONE: Fq =
+    MontFp!("25063068953384623474111414158702152701244531502492656460079210482610430750235");
+const ONE_MINUS_D_SQ: Fq = MontFp!("1159843021668779879193775521855586647937357759715417654439879720876111806838");
+const D_MINUS_ONE_SQ: Fq = MontFp!("40440834346308536858101042469323190826248399146238708352240133220865137265952");
+
+// ark-curve25519 uses 486664·x² + y² = 1 + 486660·x²·y², which is edwards25519 with x scaled by √-486664.
+// The root is the one that maps the generator of ark-curve25519 onto the edwards25519 base point.
+const EDWARDS25519_X_SCALE: Fq =
+    MontFp!("6853475219497561581579357271197624642482790079785650197046958215289687604742");
+const EDWARDS25519_X_SCALE_INV: Fq =
+    MontFp!("41479556786021360592948453331523053313939761917656469239904001243283497785092");
+
+/// (p - 5) / 8 for p = 2^255 - 19
+const SQRT_RATIO_EXPONENT: [u64; 4] =
+    [0xfffffffffffffffd, 0xffffffffffffffff, 0xffffffffffffffff, 0x0fffffffffffffff];
+
+/// 3·l + 1 with l the order of the prime subgroup. It is 1 modulo l and 0 modulo 4, so multiplying a point of
+/// the even subgroup by it drops the 4-torsion component and keeps the prime order one.
+const TORSION_FREE_MULTIPLIER: [u64; 4] =
+    [0x0837294f16e17bc8, 0x3e9ced9be8e6d683, 0x0000000000000000, 0x3000000000000000];
+
+/// Encodes the point as the 32 bytes of its ristretto255 element
+pub fn compress(point: &RistrettoPoint) -> [u8; 32] {
+    let (x_0, y_0) = point.xy().map_or((Fq::ZERO, Fq::ONE), |(x, y)| (x * EDWARDS25519_X_SCALE, y));
+    let t_0 = x_0 * y_0;
+
+    let u_1 = (Fq::ONE + y_0) * (Fq::ONE - y_0);
+    let u_2 = x_0 * y_0;
+    let (_, invsqrt) = sqrt_ratio_m1(&Fq::ONE, &(u_1 * u_2.square()));
+    let den_1 = invsqrt * u_1;
+    let den_2 = invsqrt * u_2;
+    let z_inv = den_1 * den_2 * t_0;
+
+    // Rotate the point by the 4-torsion element (i, 0) when needed, so that every coset has a single encoding
+    let (x, mut y, den_inv) = if is_negative(&(t_0 * z_inv)) {
+        (y_0 * SQRT_M1, x_0 * SQRT_M1, den_1 * INVSQRT_A_MINUS_D)
+    } else {
+        (x_0, y_0, den_2)
+    };
+    if is_negative(&(x * z_inv)) {
+        y = -y;
+    }
+    to_bytes(&abs(den_inv * (Fq::ONE - y)))
+}
+
+/// Decodes a ristretto255 element, rejecting non-canonical encodings and bytes that do not encode an element
+pub fn decompress(bytes: &[u8; 32]) -> Result<RistrettoPoint, ProofError> {
+    let s = Fq::from_le_bytes_mod_order(bytes);
+    if to_bytes(&s) != *bytes || is_negative(&s) {
+        return Err(ProofError::InvalidEncoding);
+    }
+
+    let ss = s.square();
+    let u_1 = Fq::ONE - ss;
+    let u_2 = Fq::ONE + ss;
+    let u_2_sqr = u_2.square();
+    let v = -(EDWARDS_D * u_1.square()) - u_2_sqr;
+    let (was_square, invsqrt) = sqrt_ratio_m1(&Fq::ONE, &(v * u_2_sqr));
+    let den_x = invsqrt * u_2;
+    let den_y = invsqrt * den_x * v;
+
+    let x = abs(s.double() * den_x);
+    let y = u_1 * den_y;
+    let t = x * y;
+    if !was_square || is_negative(&t) || y == Fq::ZERO {
+        return Err(ProofError::InvalidEncoding);
+    }
+    Ok(into_prime_subgroup(from_edwards25519(x, y, t, Fq::ONE)))
+}
+
+/// Maps 64 uniformly random bytes to an element of ristretto255 with the element derivation of RFC 9496
+pub fn from_uniform_bytes(bytes: &[u8; 64]) -> RistrettoPoint {
+    let p_1 = map_to_group(&field_element_from_bytes(&bytes[..32]));
+    let p_2 = map_to_group(&field_element_from_bytes(&bytes[32..]));
+    into_prime_subgroup(p_1 + p_2)
+}
+
+/// Derives num_point generators of ristretto255 from the seed by expanding it to 64 bytes with BLAKE3
+pub fn generate_n_random_points(seed: String, num_point: i32) -> Vec<RistrettoPoint> {
+    let mut random_points = Vec::<RistrettoPoint>::new();
+    let mut current_seed = seed;
+    for _ in 0..num_point {
+        let mut bytes = [0u8; 64];
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(current_seed.as_bytes());
+        hasher.finalize_xof().fill(&mut bytes);
+
+        random_points.push(from_uniform_bytes(&bytes));
+        current_seed = blake3::hash(&bytes).to_string();
+    }
+    random_points
+}
+
+impl DeriveGenerators for RistrettoPoint {
+    fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Self> {
+        generate_n_random_points(seed, num_point)
+    }
+}
+
+/// The Elligator map of RFC 9496 from a field element to a point of the even subgroup of edwards25519
+fn map_to_group(t: &Fq) -> EdwardsProjective {
+    let r = SQRT_M1 * t.square();
+    let u = (r + Fq::ONE) * ONE_MINUS_D_SQ;
+    let v = (-Fq::ONE - r * EDWARDS_D) * (r + EDWARDS_D);
+
+    let (was_square, s) = sqrt_ratio_m1(&u, &v);
+    let (s, c) = if was_square { (s, -Fq::ONE) } else { (-abs(s * t), r) };
+    let n = c * (r - Fq::ONE) * D_MINUS_ONE_SQ - v;
+
+    let w_0 = s.double() * v;
+    let w_1 = n * SQRT_AD_MINUS_ONE;
+    let w_2 = Fq::ONE - s.square();
+    let w_3 = Fq::ONE + s.square();
+    from_edwards25519(w_0 * w_3, w_2 * w_1, w_0 * w_2, w_1 * w_3)
+}
+
+/// Computes √(u/v) with the sign convention of RFC 9496. The flag is false when u/v is not a square, in which
+/// case the root of √-1·u/v is returned instead.
+fn sqrt_ratio_m1(u: &Fq, v: &Fq) -> (bool, Fq) {
+    let v_3 = v.square() * v;
+    let v_7 = v_3.square() * v;
+    let mut r = (*u * v_3) * (*u * v_7).pow(SQRT_RATIO_EXPONENT);
+    let check = *v * r.square();
+
+    let correct_sign_sqrt = check == *u;
+    let flipped_sign_sqrt = check == -*u;
+    let flipped_sign_sqrt_i = check == -*u * SQRT_M1;
+    if flipped_sign_sqrt || flipped_sign_sqrt_i {
+        r *= SQRT_M1;
+    }
+    (correct_sign_sqrt || flipped_sign_sqrt, abs(r))
+}
+
+/// Converts extended edwards25519 coordinates into a point of the curve model of ark-curve25519
+fn from_edwards25519(x: Fq, y: Fq, t: Fq, z: Fq) -> EdwardsProjective {
+    EdwardsProjective::new_unchecked(x * EDWARDS25519_X_SCALE_INV, y, t * EDWARDS25519_X_SCALE_INV, z)
+}
+
+fn into_prime_subgroup(point: EdwardsProjective) -> RistrettoPoint {
+    point.into_affine().mul_bigint(TORSION_FREE_MULTIPLIER).into_affine()
+}
+
+/// Reads the low 255 bits of the bytes as a field element, as RFC 9496 does for the element derivation
+fn field_element_from_bytes(bytes: &[u8]) -> Fq {
+    let mut masked = [0u8; 32];
+    masked.copy_from_slice(bytes);
+    masked[31] &= 0x7f;
+    Fq::from_le_bytes_mod_order(&masked)
+}
+
+fn to_bytes(value: &Fq) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&value.into_bigint().to_bytes_le());
+    bytes
+}
+
+/// A field element is negative when the least significant bit of its canonical encoding is set
+fn is_negative(value: &Fq) -> bool {
+    value.into_bigint().is_odd()
+}
+
+fn abs(value: Fq) -> Fq {
+    if is_negative(&value) {
+        -value
+    } else {
+        value
+    }
+}
@@ -0,0 +1,76 @@
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_secp256k1::{Affine, Fq, Fr};
+use sha2::{Digest, Sha256};
+
+use crate::{error::ProofError, pedersen_commitment::DeriveGenerators};
+
+/// A point of secp256k1, which has prime order and needs no cofactor handling
+pub type Secp256k1Point = Affine;
+
+/// The scalar field of secp256k1
+pub type Scalar = Fr;
+
+/// Serializes a point as 0x04 || x || y with big endian coordinates, the SEC1 uncompressed encoding
+pub fn serialize_uncompressed(point: &Secp256k1Point) -> Result<[u8; 65], ProofError> {
+    let (x, y) = point.xy().ok_or(ProofError::InvalidPoint)?;
+    let mut bytes = [0u8; 65];
+    bytes[0] = 0x04;
+    bytes[1..33].copy_from_slice(&x.into_bigint().to_bytes_be());
+    bytes[33..].copy_from_slice(&y.into_bigint().to_bytes_be());
+    Ok(bytes)
+}
+
+/// Returns the point with the big endian x coordinate and an even y coordinate, if there is one
+pub fn lift_x(x_bytes: &[u8; 32]) -> Option<Secp256k1Point> {
+    let x = Fq::from_be_bytes_mod_order(x_bytes);
+    if x.into_bigint().to_bytes_be() != x_bytes.as_slice() {
+        return None;
+    }
+    let y = (x * x * x + Fq::from(7)).sqrt()?;
+    let y = if y.into_bigint().is_even() { y } else { -y };
+    Some(Affine::new_unchecked(x, y))
+}
+
+/// Derives num_point points nobody knows a discrete logarithm of, following the convention of the secp256k1 H
+/// generator: the x coordinate is the SHA-256 hash of data and the point is the one with an even y. Hashes that
+/// are not an x coordinate on the curve are hashed again, and every further point hashes the x of the one before.
+pub fn nums_points(data: &[u8], num_point: i32) -> Vec<Secp256k1Point> {
+    let mut random_points = Vec::<Secp256k1Point>::new();
+    let mut hash: [u8; 32] = Sha256::digest(data).into();
+    for _ in 0..num_point {
+        loop {
+            let point = lift_x(&hash);
+            hash = Sha256::digest(hash).into();
+            if let Some(point) = point {
+                random_points.push(point);
+                break;
+            }
+        }
+    }
+    random_points
+}
+
+/// The generator H = lift_x(SHA-256(G)) hashed from the uncompressed encoding of the standard generator G. Its x
+/// coordinate is 0x50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0, the value generator of
+/// Pedersen commitments in libsecp256k1-zkp and Elements.
+pub fn generator_h() -> Secp256k1Point {
+    let g = serialize_uncompressed(&Secp256k1Point::generator()).expect("the generator is not the identity");
+    nums_points(&g, 1)[0]
+}
+
+/// Derives num_point generators from the bytes of the seed with nums_points
+pub fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Secp256k1Point> {
+    nums_points(seed.as_bytes(), num_point)
+}
+
+impl DeriveGenerators for Secp256k1Point {
+    fn generate_n_random_points(seed: String, num_point: i32) -> Vec<Self> {
+        generate_n_random_points(seed, num_point)
+    }
+
+    /// Commits values to H and blinding factors to the standard generator, C = H^v + G^γ, as libsecp256k1-zkp does
+    fn pedersen_generators() -> (Self, Self) {
+        (generator_h(), Self::generator())
+    }
+}
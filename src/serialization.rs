@@ -0,0 +1,120 @@
+use std::io::Read;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate};
+
+use crate::error::ProofError;
+
+/// Every serialized object starts with these bytes followed by the format version
+pub const MAGIC: &[u8; 4] = b"BPRS";
+
+/// Version of the binary format. Objects are written with compressed points in the field order of their types.
+pub const VERSION: u8 = 1;
+
+/// Serializes a proof, commitment or polynomial with the versioned header and compressed points
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, ProofError> {
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + value.compressed_size());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    value.serialize_compressed(&mut bytes).map_err(|_| ProofError::InvalidEncoding)?;
+    Ok(bytes)
+}
+
+/// Deserializes an object written by to_bytes. Every point is checked to lie on the curve and in the prime order
+/// subgroup, and trailing bytes are rejected. The proofs and vectors of this crate read their length prefixes with
+/// deserialize_vec, so a malformed length fails with InvalidEncoding instead of exhausting memory.
+pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, ProofError> {
+    let Some((header, mut body)) = bytes.split_at_checked(MAGIC.len() + 1) else {
+        return Err(ProofError::InvalidEncoding);
+    };
+    if &header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != VERSION {
+        return Err(ProofError::InvalidEncoding);
+    }
+
+    let value = T::deserialize_with_mode(&mut body, Compress::Yes, Validate::No)
+        .map_err(|_| ProofError::InvalidEncoding)?;
+    if !body.is_empty() {
+        return Err(ProofError::InvalidEncoding);
+    }
+    value.check().map_err(|_| ProofError::InvalidPoint)?;
+    Ok(value)
+}
+
+/// Reads a vector in the layout of ark-serialize: a u64 length followed by the elements. The Vec implementation of
+/// ark-serialize reserves capacity for the declared length before reading anything, so an untrusted length prefix
+/// could make it allocate without bound. Here the vector only grows as elements are actually read, and a length
+/// larger than the remaining input fails on the first missing element.
+pub(crate) fn deserialize_vec<T: CanonicalDeserialize, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<Vec<T>, SerializationError> {
+    let len = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+    let mut values = Vec::new();
+    for _ in 0..len {
+        values.push(T::deserialize_with_mode(&mut reader, compress, validate)?);
+    }
+    Ok(values)
+}
+
+/// Serde adapters that write a point or scalar as the hex string of its compressed encoding. Proof fields use
+/// them through `#[serde(with = ...)]`, and commitments embedded in other documents can use them the same way.
+#[cfg(feature = "serde")]
+pub mod hex {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(value.compressed_size());
+        value.serialize_compressed(&mut bytes).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&::hex::encode(bytes))
+    }
+
+    /// Decodes the hex string and checks that a point lies on the curve and in the prime order subgroup
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = ::hex::decode(encoded).map_err(D::Error::custom)?;
+        let mut reader = bytes.as_slice();
+        let value = T::deserialize_compressed(&mut reader).map_err(D::Error::custom)?;
+        if !reader.is_empty() {
+            return Err(D::Error::custom("trailing bytes after encoded value"));
+        }
+        Ok(value)
+    }
+
+    /// The same encoding for every element of a vector, written as a sequence of hex strings
+    pub mod vec {
+        use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+        use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<T: CanonicalSerialize, S: Serializer>(values: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(values.len()))?;
+            for value in values {
+                seq.serialize_element(&Hex(value))?;
+            }
+            seq.end()
+        }
+
+        pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<T>, D::Error> {
+            let values = Vec::<Unhex<T>>::deserialize(deserializer)?;
+            Ok(values.into_iter().map(|value| value.0).collect())
+        }
+
+        struct Hex<'a, T>(&'a T);
+
+        impl<T: CanonicalSerialize> Serialize for Hex<'_, T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                super::serialize(self.0, serializer)
+            }
+        }
+
+        struct Unhex<T>(T);
+
+        impl<'de, T: CanonicalDeserialize> Deserialize<'de> for Unhex<T> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                super::deserialize(deserializer).map(Unhex)
+            }
+        }
+    }
+}
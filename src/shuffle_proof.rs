@@ -0,0 +1,75 @@
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::{check_length, ProofError},
+    gadgets::shuffle,
+    pedersen_commitment::DeriveGenerators,
+    r1cs::{Prover, R1csProof, Verifier},
+    transcript::Transcript,
+};
+
+/// Proves that the committed outputs are a permutation of the committed inputs, returning the proof together
+/// with the input commitments G^x_i + B^γ_i and output commitments G^y_i + B^δ_i it is verified against.
+/// The outputs are blinded independently, so the proof hides which input went where.
+#[allow(clippy::type_complexity)]
+pub fn prove_shuffle<G: DeriveGenerators, R: RngCore + CryptoRng>(
+    input_values: &[G::ScalarField],
+    input_blindings: &[G::ScalarField],
+    output_values: &[G::ScalarField],
+    output_blindings: &[G::ScalarField],
+    rng: &mut R,
+) -> Result<(R1csProof<G>, Vec<G>, Vec<G>), ProofError> {
+    let k = input_values.len();
+    if k == 0 {
+        return Err(ProofError::EmptyInput);
+    }
+    check_length(k, input_blindings.len())?;
+    check_length(k, output_values.len())?;
+    check_length(k, output_blindings.len())?;
+
+    let mut transcript = shuffle_transcript(k);
+    let mut prover = Prover::new(&mut transcript);
+
+    let (inputs, x): (Vec<_>, Vec<_>) = input_values
+        .iter()
+        .zip(input_blindings.iter())
+        .map(|(v, gamma)| prover.commit(*v, *gamma))
+        .unzip();
+    let (outputs, y): (Vec<_>, Vec<_>) = output_values
+        .iter()
+        .zip(output_blindings.iter())
+        .map(|(v, gamma)| prover.commit(*v, *gamma))
+        .unzip();
+
+    shuffle(&mut prover, &x, &y)?;
+    let proof = prover.prove(rng)?;
+    Ok((proof, inputs, outputs))
+}
+
+/// Verifies that the values committed in outputs are a permutation of the values committed in inputs
+pub fn verify_shuffle<G: DeriveGenerators>(
+    inputs: Vec<G>,
+    outputs: Vec<G>,
+    proof: &R1csProof<G>,
+) -> Result<(), ProofError> {
+    let k = inputs.len();
+    if k == 0 {
+        return Err(ProofError::EmptyInput);
+    }
+    check_length(k, outputs.len())?;
+
+    let mut transcript = shuffle_transcript(k);
+    let mut verifier = Verifier::new(&mut transcript);
+
+    let x: Vec<_> = inputs.into_iter().map(|c| verifier.commit(c)).collect();
+    let y: Vec<_> = outputs.into_iter().map(|c| verifier.commit(c)).collect();
+
+    shuffle(&mut verifier, &x, &y)?;
+    verifier.verify(proof)
+}
+
+fn shuffle_transcript(k: usize) -> Transcript {
+    let mut transcript = Transcript::new(b"shuffle_proof");
+    transcript.append_u64(b"k", k as u64);
+    transcript
+}
@@ -1,45 +1,57 @@
-use std::error::Error;
-
-use ark_bn254::{Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_ff::{AdditiveGroup, Field};
+use ark_std::cfg_chunks;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    error::{check_length, ProofError},
+    log_ipa_proof::join,
+    msm::msm,
+    transcript::Transcript,
+};
 
 /// Computes the commitment C which is the sum of each generator g_i multiplied by the corresponding scalar a_i
-pub fn commit(
-    committing_vector: &Vec<F>,
-    g_vec: &Vec<G1Affine>,
-) -> Result<G1Affine, Box<dyn Error>> {
-    if committing_vector.len() != g_vec.len() {
-        return Err("Invalid vector lengths".into());
-    }
-    let mut result: G1Affine = G1Affine::zero();
-    for (index, point) in committing_vector.iter().enumerate() {
-        // C += g_i * a_i
-        result = (result + g_vec[index] * point).into_affine();
-    }
-    Ok(result)
+pub fn commit<G: AffineRepr>(committing_vector: &[G::ScalarField], g_vec: &[G]) -> Result<G, ProofError> {
+    check_length(g_vec.len(), committing_vector.len())?;
+    // C = Σ_i g_i * a_i
+    Ok(msm(committing_vector, g_vec)?.into_affine())
 }
 
 /// Computes the commitments C_a, L, and R by committing to vectors a, l, and r respectively
-pub fn commit_vector(a: &mut Vec<F>, g_vec: &mut Vec<G1Affine>) -> (G1Affine, G1Affine, G1Affine) {
-    let _a = commit(a, g_vec).unwrap();
+pub fn commit_vector<G: AffineRepr>(
+    a: &mut Vec<G::ScalarField>,
+    g_vec: &mut Vec<G>,
+) -> Result<(G, G, G), ProofError> {
+    let _a = commit(a, g_vec)?;
+
+    let (_l, _r) = compute_secondary_diagonal(g_vec, a)?;
 
-    let (_l, _r) = compute_secondary_diagonal(g_vec, a);
+    Ok((_a, _l, _r))
+}
+
+/// Absorbs the commitments C_a, L and R into the transcript and derives the folding challenge u from them
+pub fn generate_challenge<G: AffineRepr>(transcript: &mut Transcript, committments: &(G, G, G)) -> G::ScalarField {
+    let (_a, _l, _r) = committments;
 
-    (_a, _l, _r)
+    transcript.domain_separator(b"succinct_proof");
+    transcript.append_point(b"A", _a);
+    transcript.append_point(b"L", _l);
+    transcript.append_point(b"R", _r);
+    transcript.challenge_scalar(b"u")
 }
 
 /// Verifies the succinct proof by checking if L multiplied by u squared plus C_a plus R multiplied by u inverse squared equals C_proof
-pub fn verify_succinct_proof(
-    committments: &(G1Affine, G1Affine, G1Affine),
-    proof: &Vec<F>,
-    u: &F,
-    g_vec: &mut Vec<G1Affine>,
-) -> bool {
+pub fn verify_succinct_proof<G: AffineRepr>(
+    committments: &(G, G, G),
+    proof: &[G::ScalarField],
+    u: &G::ScalarField,
+    g_vec: &mut Vec<G>,
+) -> Result<(), ProofError> {
     let (_a, _l, _r) = committments;
 
     // Compute u squared inverse
-    let u_square_inv = (u * u).inverse().unwrap();
+    let u_square_inv = (*u * u).inverse().ok_or(ProofError::ZeroChallenge)?;
     // Compute L multiplied by u squared
     let l_u_squared = (*_l * u * u).into_affine();
     // Compute R multiplied by u inverse squared
@@ -48,45 +60,50 @@ pub fn verify_succinct_proof(
     let lhs = (l_u_squared + *_a + r_u_inv_squared).into_affine();
 
     // Fold the generator vector with u inverse
-    let u_inv = u.inverse().unwrap();
-    let folded_g_vec = fold_group(g_vec, &u_inv);
+    let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
+    let folded_g_vec = fold_group(g_vec, &u_inv)?;
     // Compute right-hand side: C_proof is commit(proof, folded_g_vec)
-    let rhs = commit(proof, &folded_g_vec).unwrap();
+    let rhs = commit(proof, &folded_g_vec)?;
 
-    lhs == rhs
+    if lhs != rhs {
+        return Err(ProofError::VerificationFailure);
+    }
+    Ok(())
 }
 
 /// Folds the field vector a into a new vector a_prime where each element a'_i is a_2i multiplied by u plus a_2i+1 multiplied by u inverse
-pub fn fold_field(a: &mut Vec<F>, u: &F) -> Vec<F> {
-    // if a.len() % 2 != 0 {
-    //     a.push(F::ZERO);
-    // }
-
-    let mut result = Vec::<F>::new();
-    for chunk in a.chunks(2) {
-        // a_prime = a_0 * u + a_1 * u_inverse
-        result.push(chunk[0] * u + chunk[1] * u.inverse().unwrap());
+pub fn fold_field<F: Field>(a: &mut Vec<F>, u: &F) -> Result<Vec<F>, ProofError> {
+    if !a.len().is_multiple_of(2) {
+        a.push(F::ZERO);
     }
 
-    result
+    let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
+    Ok(cfg_chunks!(a, 2)
+        .map(|chunk| {
+            // a_prime = a_0 * u + a_1 * u_inverse
+            chunk[0] * u + chunk[1] * u_inv
+        })
+        .collect())
 }
 
 /// Folds the group vector G into a new vector G_prime where each element G'_i is G_2i multiplied by u plus G_2i+1 multiplied by u inverse
-pub fn fold_group(a: &mut Vec<G1Affine>, u: &F) -> Vec<G1Affine> {
-    // if a.len() % 2 != 0 {
-    //     a.push(G1Affine::zero());
-    // }
-    a.chunks(2)
+pub fn fold_group<G: AffineRepr>(a: &mut Vec<G>, u: &G::ScalarField) -> Result<Vec<G>, ProofError> {
+    if !a.len().is_multiple_of(2) {
+        a.push(G::zero());
+    }
+    let u_inv = u.inverse().ok_or(ProofError::ZeroChallenge)?;
+    let folded: Vec<G::Group> = cfg_chunks!(a, 2)
         .map(|chunk| {
             // G_prime = G_0 * u + G_1 * u_inverse
-            (chunk[0] * u + chunk[1] * u.inverse().unwrap()).into_affine()
+            chunk[0] * u + chunk[1] * u_inv
         })
-        .collect()
+        .collect();
+    Ok(G::Group::normalize_batch(&folded))
 }
 
 /// Splits the vector a into two vectors l and r where each element l_i is a_2i and r_i is a_2i+1
 pub fn split_vector<T: Clone + Default>(a: &mut Vec<T>) -> (Vec<T>, Vec<T>) {
-    if a.len() % 2 != 0 {
+    if !a.len().is_multiple_of(2) {
         a.push(T::default());
     }
     let l: Vec<T> = a.chunks(2).map(|chunk| chunk[0].clone()).collect();
@@ -95,27 +112,28 @@ pub fn split_vector<T: Clone + Default>(a: &mut Vec<T>) -> (Vec<T>, Vec<T>) {
 }
 
 /// Computes the secondary diagonal commitments L and R by committing to vectors l and r with generators g' and g'' respectively
-pub fn compute_secondary_diagonal(
-    g_vec: &mut Vec<G1Affine>,
-    a: &mut Vec<F>,
-) -> (G1Affine, G1Affine) {
+pub fn compute_secondary_diagonal<G: AffineRepr>(
+    g_vec: &mut Vec<G>,
+    a: &mut Vec<G::ScalarField>,
+) -> Result<(G, G), ProofError> {
 
-    assert_eq!(a.len(), g_vec.len(), "length of Vec a need to match length og g_vec vector");
+    check_length(g_vec.len(), a.len())?;
 
-    if a.len() % 2 != 0 {
-        a.push(F::ZERO);
-        g_vec.push(G1Affine::zero());
+    if !a.len().is_multiple_of(2) {
+        a.push(G::ScalarField::ZERO);
+        g_vec.push(G::zero());
     }
 
     // Split a into l and r
-    let l: Vec<F> = a.chunks(2).map(|chunk| chunk[0].clone()).collect();
-    let r: Vec<F> = a.chunks(2).map(|chunk| chunk[1].clone()).collect();
+    let l: Vec<G::ScalarField> = a.chunks(2).map(|chunk| chunk[0]).collect();
+    let r: Vec<G::ScalarField> = a.chunks(2).map(|chunk| chunk[1]).collect();
 
     // Split g into g_prime and g_double_prime
-    let g1_vec: Vec<G1Affine> = g_vec.chunks(2).map(|chunk| chunk[0].clone()).collect();
-    let g2_vec: Vec<G1Affine> = g_vec.chunks(2).map(|chunk| chunk[1].clone()).collect();
+    let g1_vec: Vec<G> = g_vec.chunks(2).map(|chunk| chunk[0]).collect();
+    let g2_vec: Vec<G> = g_vec.chunks(2).map(|chunk| chunk[1]).collect();
 
 
     // Compute L by committing to l with g2_vec and R by committing to r with g1_vec
-    (commit(&l, &g2_vec).unwrap(), commit(&r, &g1_vec).unwrap())
+    let (l_com, r_com) = join(|| commit(&l, &g2_vec), || commit(&r, &g1_vec));
+    Ok((l_com?, r_com?))
 }
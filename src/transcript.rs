@@ -0,0 +1,67 @@
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+/// A Fiat-Shamir transcript in the style of Merlin: every message is absorbed together with a
+/// label, and challenges are squeezed from the running hash of everything absorbed so far.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: blake3::Hasher,
+}
+
+impl Transcript {
+    /// Starts a new transcript bound to the protocol name `label`
+    pub fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            hasher: blake3::Hasher::new(),
+        };
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    /// Separates the messages of a sub-protocol from the ones that came before it
+    pub fn domain_separator(&mut self, label: &'static [u8]) {
+        self.append_message(b"dom-sep", label);
+    }
+
+    /// Absorbs `message` under `label`; both are length-prefixed so that distinct sequences of
+    /// messages can never hash to the same state
+    pub fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.hasher.update(&(label.len() as u64).to_le_bytes());
+        self.hasher.update(label);
+        self.hasher.update(&(message.len() as u64).to_le_bytes());
+        self.hasher.update(message);
+    }
+
+    pub fn append_u64(&mut self, label: &'static [u8], value: u64) {
+        self.append_message(label, &value.to_le_bytes());
+    }
+
+    /// Absorbs the compressed encoding of a curve point
+    pub fn append_point<P: CanonicalSerialize>(&mut self, label: &'static [u8], point: &P) {
+        let mut bytes = Vec::new();
+        point
+            .serialize_compressed(&mut bytes)
+            .expect("serialization into a Vec cannot fail");
+        self.append_message(label, &bytes);
+    }
+
+    pub fn append_scalar<S: CanonicalSerialize>(&mut self, label: &'static [u8], scalar: &S) {
+        self.append_point(label, scalar);
+    }
+
+    /// Squeezes a challenge scalar out of the transcript. The challenge is absorbed back into the
+    /// transcript so that consecutive challenges are independent of each other.
+    pub fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F {
+        let mut hasher = self.hasher.clone();
+        hasher.update(b"challenge");
+        hasher.update(label);
+
+        // Reduce 64 bytes of output so the challenge is statistically close to uniform
+        let mut bytes = [0u8; 64];
+        hasher.finalize_xof().fill(&mut bytes);
+        let challenge = F::from_le_bytes_mod_order(&bytes);
+
+        self.append_scalar(label, &challenge);
+        challenge
+    }
+}
@@ -1,19 +1,23 @@
-use std::{
-    fmt::Display,
-    ops::{Add, Mul},
-};
+use std::{fmt::Display, io::Read};
 
-use ark_bls12_381::{Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
-use rand::Rng;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::{check_length, ProofError},
+    msm::msm,
+    serialization::deserialize_vec,
+};
 
 /// Represents a vector of field elements
-#[derive(Clone, Debug)]
-pub struct Coeff(pub Vec<F>);
+#[derive(Clone, Debug, CanonicalSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coeff<F: PrimeField>(#[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex::vec"))] pub Vec<F>);
 
-impl Coeff {
+impl<F: PrimeField> Coeff<F> {
     pub fn new(values: Vec<F>) -> Self {
         Self(values)
     }
@@ -22,12 +26,9 @@ impl Coeff {
         Self(values.to_vec())
     }
 
-    pub fn random(len: usize) -> Self {
-        let mut rng = rand::thread_rng();
-        let random_coeff: Vec<F> = (0..len)
-            .map(|_| F::from(rng.gen_range(1..100000000)))
-            .collect();
-        Self(random_coeff)
+    /// Samples every element uniformly from the whole scalar field
+    pub fn random<R: RngCore + CryptoRng>(len: usize, rng: &mut R) -> Self {
+        Self((0..len).map(|_| F::rand(rng)).collect())
     }
 
     pub fn zero(len: usize) -> Self {
@@ -38,14 +39,9 @@ impl Coeff {
         Self(vec![F::ONE; len])
     }
 
-    pub fn commit(&self, g_vec: &Vec<G1Affine>) -> G1Affine {
-        assert!(self.len() == g_vec.len());
-        self.0
-            .iter()
-            .zip(g_vec.iter())
-            .fold(G1Affine::zero(), |acc, (a, g)| {
-                (acc + (*g * a).into_affine()).into_affine()
-            })
+    /// Commits to the vector over any curve whose scalar field is F
+    pub fn commit<G: AffineRepr<ScalarField = F>>(&self, g_vec: &[G]) -> Result<G, ProofError> {
+        Ok(msm(&self.0, g_vec)?.into_affine())
     }
 
     pub fn len(&self) -> usize {
@@ -55,22 +51,34 @@ impl Coeff {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Element-wise sum, failing unless both vectors have the same length
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, ProofError> {
+        check_length(self.len(), rhs.len())?;
+        Ok(Coeff(self.0.iter().zip(rhs.0.iter()).map(|(a, b)| *a + b).collect()))
+    }
+
+    /// Element-wise (Hadamard) product, failing unless both vectors have the same length
+    pub fn checked_mul(&self, rhs: &Self) -> Result<Self, ProofError> {
+        check_length(self.len(), rhs.len())?;
+        Ok(Coeff(self.0.iter().zip(rhs.0.iter()).map(|(a, b)| *a * b).collect()))
+    }
 }
 
 // Trait implementations for Coeff
-impl From<Vec<F>> for Coeff {
+impl<F: PrimeField> From<Vec<F>> for Coeff<F> {
     fn from(values: Vec<F>) -> Self {
         Self(values)
     }
 }
 
-impl From<Coeff> for Vec<F> {
-    fn from(coeff: Coeff) -> Self {
+impl<F: PrimeField> From<Coeff<F>> for Vec<F> {
+    fn from(coeff: Coeff<F>) -> Self {
         coeff.0
     }
 }
 
-impl std::ops::Index<usize> for Coeff {
+impl<F: PrimeField> std::ops::Index<usize> for Coeff<F> {
     type Output = F;
 
     fn index(&self, index: usize) -> &Self::Output {
@@ -78,33 +86,30 @@ impl std::ops::Index<usize> for Coeff {
     }
 }
 
-impl std::ops::IndexMut<usize> for Coeff {
+impl<F: PrimeField> std::ops::IndexMut<usize> for Coeff<F> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
 
-impl Add for Coeff {
-    type Output = Coeff;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        assert!(self.0.len() == rhs.0.len());
-        let coeff = self.0.into_iter().zip(rhs.0).map(|(a, b)| a + b).collect();
-        Coeff(coeff)
+impl<F: PrimeField> Valid for Coeff<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
     }
 }
 
-impl Mul for Coeff {
-    type Output = Coeff;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        assert!(self.0.len() == rhs.0.len());
-        let result = self.0.into_iter().zip(rhs.0).map(|(a, b)| a * b).collect();
-        Coeff(result)
+// The elements are read without trusting the length prefix, see deserialize_vec
+impl<F: PrimeField> CanonicalDeserialize for Coeff<F> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self(deserialize_vec(reader, compress, validate)?))
     }
 }
 
-impl PartialEq for Coeff {
+impl<F: PrimeField> PartialEq for Coeff<F> {
     fn eq(&self, other: &Self) -> bool {
         self.0.len() == other.0.len() && self.0.iter().zip(other.0.iter()).all(|(a, b)| a == b)
     }
@@ -112,58 +117,89 @@ impl PartialEq for Coeff {
 
 pub trait InnerProduct {
     type Output;
-    fn inner_product(&self, rhs: &Self) -> Self::Output;
+    fn inner_product(&self, rhs: &Self) -> Result<Self::Output, ProofError>;
 }
 
-impl InnerProduct for Coeff {
+impl<F: PrimeField> InnerProduct for Coeff<F> {
     type Output = F;
 
-    fn inner_product(&self, rhs: &Self) -> Self::Output {
-        assert!(self.0.len() == rhs.0.len());
-        self.clone()
-            .0
-            .into_iter()
-            .zip(rhs.0.clone())
-            .fold(F::ZERO, |acc, (a, b)| acc + a * b)
+    /// Fails unless both vectors have the same length
+    fn inner_product(&self, rhs: &Self) -> Result<Self::Output, ProofError> {
+        check_length(self.len(), rhs.len())?;
+        Ok(self.0.iter().zip(rhs.0.iter()).fold(F::ZERO, |acc, (a, b)| acc + *a * b))
     }
 }
 
-/// Represents a polynomial with vector coefficients
-#[derive(Debug, Clone)]
-pub struct VectorPolynomial {
-    pub coeffs: Vec<Coeff>,
+/// Represents a polynomial with vector coefficients. All coefficient vectors have the same length, which is checked
+/// on construction and on deserialization.
+#[derive(Debug, Clone, CanonicalSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = "", try_from = "UncheckedVectorPolynomial<F>"))]
+pub struct VectorPolynomial<F: PrimeField> {
+    coeffs: Vec<Coeff<F>>,
 }
 
-impl VectorPolynomial {
-    pub fn new(coeffs: Vec<Coeff>) -> Self {
-        let coeffs_len: Vec<usize> = coeffs.iter().map(|coeff| coeff.0.len()).collect();
-        assert!(
-            coeffs_len.windows(2).all(|w| w[0] == w[1]),
-            "All coefficient vectors must have the same length"
-        );
-        Self { coeffs }
+impl<F: PrimeField> VectorPolynomial<F> {
+    /// Fails unless all coefficient vectors have the same length
+    pub fn new(coeffs: Vec<Coeff<F>>) -> Result<Self, ProofError> {
+        if let Some(mismatch) = coeffs.iter().find(|coeff| coeff.len() != coeffs[0].len()) {
+            return Err(ProofError::LengthMismatch {
+                expected: coeffs[0].len(),
+                found: mismatch.len(),
+            });
+        }
+        Ok(Self { coeffs })
+    }
+
+    pub fn coeffs(&self) -> &[Coeff<F>] {
+        &self.coeffs
+    }
+
+    /// The length of the coefficient vectors
+    pub fn width(&self) -> usize {
+        self.coeffs.first().map_or(0, Coeff::len)
     }
 
-    pub fn evaluate(&self, x: &F) -> Coeff {
-        let mut result = Coeff::zero(self.coeffs[0].len());
+    pub fn evaluate(&self, x: &F) -> Coeff<F> {
+        let mut result = vec![F::ZERO; self.width()];
         for (index, coeff) in self.coeffs.iter().enumerate() {
-            let term: Vec<F> = coeff
-                .0
-                .iter()
-                .map(|&val| val * x.pow(&[index as u64]))
-                .collect();
-            result = result + Coeff(term);
+            let x_i = x.pow([index as u64]);
+            for (acc, val) in result.iter_mut().zip(coeff.0.iter()) {
+                *acc += *val * x_i;
+            }
         }
-        result
+        Coeff(result)
+    }
+
+    /// Multiplies two vector polynomials through the inner product of their coefficients, failing unless both have
+    /// the same width
+    pub fn checked_mul(&self, rhs: &Self) -> Result<DensePolynomial<F>, ProofError> {
+        check_length(self.width(), rhs.width())?;
+
+        // The product of polynomials of degree d1 and d2 has degree d1 + d2
+        let result_len = (self.coeffs.len() + rhs.coeffs.len()).saturating_sub(1);
+        let mut result_coeffs = vec![F::ZERO; result_len];
+
+        for (i_index, i_coeff) in self.coeffs.iter().enumerate() {
+            for (j_index, j_coeff) in rhs.coeffs.iter().enumerate() {
+                result_coeffs[j_index + i_index] += i_coeff.inner_product(j_coeff)?;
+            }
+        }
+
+        Ok(DensePolynomial::from_coefficients_vec(result_coeffs))
     }
 
     pub fn len(&self) -> usize {
         self.coeffs.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.coeffs.is_empty()
+    }
 }
 
 // Trait implementations for VectorPolynomial
-impl Display for VectorPolynomial {
+impl<F: PrimeField> Display for VectorPolynomial<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (i, coeff) in self.coeffs.iter().enumerate() {
             if i > 0 {
@@ -182,36 +218,48 @@ impl Display for VectorPolynomial {
     }
 }
 
-impl Mul for VectorPolynomial {
-    type Output = DensePolynomial<F>;
+impl<F: PrimeField> std::ops::Index<usize> for VectorPolynomial<F> {
+    type Output = Coeff<F>;
 
-    fn mul(self, rhs: Self) -> Self::Output {
-        assert!(self.coeffs[0].0.len() == rhs.coeffs[0].0.len());
-
-        let result_len = self.coeffs.len() * rhs.coeffs.len();
-        let mut result_coeffs = vec![F::ZERO; result_len];
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.coeffs[index]
+    }
+}
 
-        for (i_index, i_coeff) in self.coeffs.iter().enumerate() {
-            for (j_index, j_coeff) in rhs.coeffs.iter().enumerate() {
-                let result_term = i_coeff.clone() * j_coeff.clone();
-                result_coeffs[j_index + i_index] += result_term.0.iter().sum::<F>();
-            }
+impl<F: PrimeField> Valid for VectorPolynomial<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.coeffs.check()?;
+        match self.coeffs.iter().all(|coeff| coeff.len() == self.width()) {
+            true => Ok(()),
+            false => Err(SerializationError::InvalidData),
         }
-
-        DensePolynomial::from_coefficients_vec(result_coeffs)
     }
 }
 
-impl std::ops::Index<usize> for VectorPolynomial {
-    type Output = Coeff;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.coeffs[index]
+impl<F: PrimeField> CanonicalDeserialize for VectorPolynomial<F> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let coeffs = deserialize_vec(reader, compress, validate)?;
+        Self::new(coeffs).map_err(|_| SerializationError::InvalidData)
     }
 }
 
-impl std::ops::IndexMut<usize> for VectorPolynomial {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.coeffs[index]
+/// The serde shape of a VectorPolynomial before its widths are checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound = "")]
+struct UncheckedVectorPolynomial<F: PrimeField> {
+    coeffs: Vec<Coeff<F>>,
+}
+
+#[cfg(feature = "serde")]
+impl<F: PrimeField> TryFrom<UncheckedVectorPolynomial<F>> for VectorPolynomial<F> {
+    type Error = ProofError;
+
+    fn try_from(poly: UncheckedVectorPolynomial<F>) -> Result<Self, Self::Error> {
+        Self::new(poly.coeffs)
     }
 }
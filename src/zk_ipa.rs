@@ -1,39 +1,66 @@
-use std::{
-    error::Error,
-    fmt::Display,
-    ops::{Add, Mul},
-    process::Output,
-};
-
-use ark_bls12_381::{Bls12_381, Config, Fq, Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
-use ark_poly::{
-    polynomial,
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
-};
-use num_bigint::BigInt;
-use rand::Rng;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
 
 use crate::{
+    error::{check_length, ProofError},
+    log_ipa_proof::{self, InnerProductProof},
+    transcript::Transcript,
     vector_polynomial::{Coeff, InnerProduct, VectorPolynomial},
     zk_mul::generate_random_field_element,
 };
 
 pub use crate::zk_mul::verify_proof;
 
-pub fn committment_vector_polynomials(
-    l_x: VectorPolynomial,
-    r_x: VectorPolynomial,
-    g_vec: &Vec<G1Affine>,
-    h_vec: &Vec<G1Affine>,
-    _g: &G1Affine,
-    _b: &G1Affine,
-) -> (Vec<G1Affine>, Vec<F>) {
-    assert!(l_x.len() == r_x.len() && l_x.len() == 2);
+/// Non-interactive zero-knowledge proof that <a, b> is the value committed in a published C_v.
+/// Instead of l(u) and r(u) themselves the proof carries an inner product argument for them, so it holds
+/// 2*log2(n) group elements besides the four committments.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ZkIpaProof<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_a: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_s: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t1: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t2: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t_u: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub pi_lr: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub pi_t: G::ScalarField,
+    pub ipa_proof: InnerProductProof<G>,
+}
+
+impl<G: AffineRepr> ZkIpaProof<G> {
+    /// The committments in the order they are absorbed into the transcript
+    pub fn committments(&self, c_v: &G) -> Vec<G> {
+        vec![self.c_a, self.c_s, *c_v, self.c_t1, self.c_t2]
+    }
+}
 
-    assert!(g_vec.len() == h_vec.len());
+pub fn committment_vector_polynomials<G: AffineRepr, R: RngCore + CryptoRng>(
+    l_x: VectorPolynomial<G::ScalarField>,
+    r_x: VectorPolynomial<G::ScalarField>,
+    g_vec: &[G],
+    h_vec: &[G],
+    _g: &G,
+    _b: &G,
+    rng: &mut R,
+) -> Result<(Vec<G>, Vec<G::ScalarField>), ProofError> {
+    // Only linear vector polynomials l(x) and r(x) are supported
+    for poly in [&l_x, &r_x] {
+        if poly.len() != 2 {
+            return Err(ProofError::UnsupportedDegree(poly.len().saturating_sub(1)));
+        }
+    }
+    check_length(l_x.width(), r_x.width())?;
+    check_length(g_vec.len(), h_vec.len())?;
 
     // l(x) = a + s_l*x : Extract vector coefficients
     let a = l_x[0].clone();    // constant term vector
@@ -44,37 +71,55 @@ pub fn committment_vector_polynomials(
     let s_r = r_x[1].clone(); // coefficient vector of x
 
     // v = <a,b> : Inner product of constant terms
-    let v = a.inner_product(&b);
+    let v = a.inner_product(&b)?;
 
-    let alpha = generate_random_field_element();
+    let alpha: G::ScalarField = generate_random_field_element(rng);
     // C_a = Com(a,b; α) = ∑g_i^a_i + ∑h_i^b_i + B^α
-    let _a = (a.commit(g_vec) + b.commit(h_vec) + (*_b * alpha).into_affine()).into_affine();
+    let _a = (a.commit(g_vec)? + b.commit(h_vec)? + (*_b * alpha).into_affine()).into_affine();
 
-    let beta = generate_random_field_element();
+    let beta: G::ScalarField = generate_random_field_element(rng);
     // C_s = Com(s_l,s_r; β) = ∑g_i^s_l_i + ∑h_i^s_r_i + B^β
-    let _s = (s_l.commit(g_vec) + s_r.commit(h_vec) + (*_b * beta)).into_affine();
+    let _s = (s_l.commit(g_vec)? + s_r.commit(h_vec)? + (*_b * beta)).into_affine();
 
-    let gamma = generate_random_field_element();
+    let gamma: G::ScalarField = generate_random_field_element(rng);
     // C_v = Com(v; γ) = G^v + B^γ
     let _v = (*_g * v + *_b * gamma).into_affine();
 
-    let tau_1 = generate_random_field_element();
+    let tau_1: G::ScalarField = generate_random_field_element(rng);
     // C_t1 = Com(<a,s_r> + <b,s_l>; τ_1) = G^(<a,s_r> + <b,s_l>) + B^τ_1
-    let _t_1 = (*_g * (a.inner_product(&s_r) + b.inner_product(&s_l)) + *_b * tau_1).into_affine();
+    let _t_1 = (*_g * (a.inner_product(&s_r)? + b.inner_product(&s_l)?) + *_b * tau_1).into_affine();
 
-    let tau_2 = generate_random_field_element();
+    let tau_2: G::ScalarField = generate_random_field_element(rng);
     // C_t2 = Com(<s_l,s_r>; τ_2) = G^<s_l,s_r> + B^τ_2
-    let _t_2 = (*_g * s_l.inner_product(&s_r) + *_b * tau_2).into_affine();
+    let _t_2 = (*_g * s_l.inner_product(&s_r)? + *_b * tau_2).into_affine();
 
-    return (
+    Ok((
         vec![_a, _s, _v, _t_1, _t_2],
         vec![alpha, beta, gamma, tau_1, tau_2],
-    );
+    ))
 }
 
-pub fn generate_proof(blinding_factors: &Vec<F>, u: &F) -> (F, F) {
-    let [alpha, beta, gamma, tau_1, tau_2] = blinding_factors.as_slice() else {
-        panic!("Expected exactly 5 blinding factors");
+/// Absorbs C_a, C_s, C_v, C_t1 and C_t2 into the transcript and derives the evaluation point u from them
+pub fn generate_challenge<G: AffineRepr>(
+    transcript: &mut Transcript,
+    committments: &[G],
+) -> Result<G::ScalarField, ProofError> {
+    let [_a, _s, _v, _t_1, _t_2] = committments else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: committments.len() });
+    };
+
+    transcript.domain_separator(b"zk_ipa");
+    transcript.append_point(b"C_a", _a);
+    transcript.append_point(b"C_s", _s);
+    transcript.append_point(b"C_v", _v);
+    transcript.append_point(b"C_t1", _t_1);
+    transcript.append_point(b"C_t2", _t_2);
+    Ok(transcript.challenge_scalar(b"u"))
+}
+
+pub fn generate_proof<F: Field>(blinding_factors: &[F], u: &F) -> Result<(F, F), ProofError> {
+    let [alpha, beta, gamma, tau_1, tau_2] = blinding_factors else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: blinding_factors.len() });
     };
 
     // π_lr = α + β*u : Linear combination of blinding factors for vectors
@@ -82,38 +127,134 @@ pub fn generate_proof(blinding_factors: &Vec<F>, u: &F) -> (F, F) {
     // π_t = γ + τ_1*u + τ_2*u^2 : Linear combination of blinding factors for inner products
     let pi_t = *gamma + *tau_1 * u + *tau_2 * u * u;
 
-    (pi_lr, pi_t)
+    Ok((pi_lr, pi_t))
 }
 
-pub fn verify_ipa(
-    l_u: Coeff,
-    r_u: Coeff,
-    t_u: &F,
-    u: &F,
-    committments: &Vec<G1Affine>,
-    proofs: &(F, F),
-    g_vec: Vec<G1Affine>,
-    h_vec: Vec<G1Affine>,
-    _g: &G1Affine,
-    _b: &G1Affine,
-) -> bool {
-    let [_a, _s, _v, _t_1, _t_2] = committments.as_slice() else {
-        panic!("Expected exactly 5 blinding factors");
+#[allow(clippy::too_many_arguments)]
+pub fn verify_ipa<G: AffineRepr>(
+    l_u: Coeff<G::ScalarField>,
+    r_u: Coeff<G::ScalarField>,
+    t_u: &G::ScalarField,
+    u: &G::ScalarField,
+    committments: &[G],
+    proofs: &(G::ScalarField, G::ScalarField),
+    g_vec: Vec<G>,
+    h_vec: Vec<G>,
+    _g: &G,
+    _b: &G,
+) -> Result<(), ProofError> {
+    let [_a, _s, _v, _t_1, _t_2] = committments else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: committments.len() });
     };
+    check_length(l_u.len(), r_u.len())?;
 
     let (pi_lr, pi_t) = proofs;
 
     // Check 1: C_a + u*C_s = Com(l(u),r(u); π_lr)
     // Verifies the commitment to the vector polynomial evaluations
     let lhs_1 = (*_a + (*_s * u).into_affine()).into_affine();
-    let rhs_1 = (l_u.commit(&g_vec) + r_u.commit(&h_vec) + (*_b * pi_lr).into_affine()).into_affine();
+    let rhs_1 = (l_u.commit(&g_vec)? + r_u.commit(&h_vec)? + (*_b * pi_lr).into_affine()).into_affine();
 
     // Check 2: G^t(u) + B^π_t = C_v + u*C_t1 + u^2*C_t2
     // Verifies the commitment to the inner product polynomial evaluation
     let lhs_2 = (*_g * t_u + *_b * pi_t).into_affine();
-    let rhs_2 = (*_v + (*_t_1 * u + *_t_2 * (u * u)).into_affine()).into_affine();
+    let rhs_2 = (*_v + (*_t_1 * u + *_t_2 * (*u * u)).into_affine()).into_affine();
 
     // Check 3: t(u) = <l(u),r(u)>
     // Verifies that the claimed evaluation equals the inner product
-    (lhs_1 == rhs_1) && (lhs_2 == rhs_2) && (*t_u == l_u.inner_product(&r_u))
+    if (lhs_1 != rhs_1) || (lhs_2 != rhs_2) || (*t_u != l_u.inner_product(&r_u)?) {
+        return Err(ProofError::VerificationFailure);
+    }
+    Ok(())
+}
+
+/// Proves that <a, b> = v, returning the proof together with the commitment C_v = G^v + B^γ to publish.
+/// The blinding vectors s_l, s_r and the blinding factors are sampled from rng and u is derived from the transcript.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_zk_ipa<G: AffineRepr, R: RngCore + CryptoRng>(
+    a_vec: &Coeff<G::ScalarField>,
+    b_vec: &Coeff<G::ScalarField>,
+    g_vec: &[G],
+    h_vec: &[G],
+    _g: &G,
+    _b: &G,
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> Result<(ZkIpaProof<G>, G), ProofError> {
+    check_length(a_vec.len(), b_vec.len())?;
+    if !a_vec.len().is_power_of_two() {
+        return Err(ProofError::NotPowerOfTwo(a_vec.len()));
+    }
+
+    // l(x) = a + s_l*x, r(x) = b + s_r*x
+    let l_x = VectorPolynomial::new(vec![a_vec.clone(), Coeff::random(a_vec.len(), rng)])?;
+    let r_x = VectorPolynomial::new(vec![b_vec.clone(), Coeff::random(b_vec.len(), rng)])?;
+
+    let (committments, blinding_factors) =
+        committment_vector_polynomials(l_x.clone(), r_x.clone(), g_vec, h_vec, _g, _b, rng)?;
+    let u = generate_challenge(transcript, &committments)?;
+    let (pi_lr, pi_t) = generate_proof(&blinding_factors, &u)?;
+
+    let l_u = l_x.evaluate(&u);
+    let r_u = r_x.evaluate(&u);
+    let t_u = l_u.inner_product(&r_u)?;
+
+    // Rather than sending l(u) and r(u), prove <l(u), r(u)> = t(u) with the logarithmic argument
+    let q = inner_product_base(transcript, &t_u, &pi_lr, &pi_t, _g);
+    let ipa_proof = log_ipa_proof::prove(transcript, g_vec, h_vec, &q, &l_u.0, &r_u.0)?;
+
+    let proof = ZkIpaProof {
+        c_a: committments[0],
+        c_s: committments[1],
+        c_t1: committments[3],
+        c_t2: committments[4],
+        t_u,
+        pi_lr,
+        pi_t,
+        ipa_proof,
+    };
+    Ok((proof, committments[2]))
+}
+
+/// Verifies a ZkIpaProof against the published commitment C_v, re-deriving u from the transcript
+pub fn verify_zk_ipa<G: AffineRepr>(
+    c_v: &G,
+    proof: &ZkIpaProof<G>,
+    g_vec: &[G],
+    h_vec: &[G],
+    _g: &G,
+    _b: &G,
+    transcript: &mut Transcript,
+) -> Result<(), ProofError> {
+    let committments = proof.committments(c_v);
+    let u = generate_challenge(transcript, &committments)?;
+
+    // Check 1: G^t(u) + B^π_t = C_v + u*C_t1 + u^2*C_t2
+    let lhs = (*_g * proof.t_u + *_b * proof.pi_t).into_affine();
+    let rhs = (*c_v + proof.c_t1 * u + proof.c_t2 * (u * u)).into_affine();
+    if lhs != rhs {
+        return Err(ProofError::VerificationFailure);
+    }
+
+    // Check 2: C_a + u*C_s - B^π_lr + Q^t(u) = <l(u), g> + <r(u), h> + Q^<l(u), r(u)>, proven by the
+    // inner product argument
+    let q = inner_product_base(transcript, &proof.t_u, &proof.pi_lr, &proof.pi_t, _g);
+    let p = (proof.c_a + proof.c_s * u - *_b * proof.pi_lr + q * proof.t_u).into_affine();
+    log_ipa_proof::verify(transcript, g_vec, h_vec, &q, &p, &proof.ipa_proof)
+}
+
+/// Absorbs the scalars of the proof and derives Q = G^w, the base the inner product argument commits t(u) to.
+/// Scaling G by a challenge keeps the prover from moving value between t(u) and the rest of P.
+pub(crate) fn inner_product_base<G: AffineRepr>(
+    transcript: &mut Transcript,
+    t_u: &G::ScalarField,
+    pi_lr: &G::ScalarField,
+    pi_t: &G::ScalarField,
+    _g: &G,
+) -> G {
+    transcript.append_scalar(b"t_u", t_u);
+    transcript.append_scalar(b"pi_lr", pi_lr);
+    transcript.append_scalar(b"pi_t", pi_t);
+    let w: G::ScalarField = transcript.challenge_scalar(b"w");
+    (*_g * w).into_affine()
 }
@@ -1,35 +1,58 @@
-use std::error::Error;
-
-use ark_bls12_381::{Bls12_381, Config, Fq, Fr as F, G1Affine};
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, PrimeField};
-use ark_poly::{
-    polynomial,
-    univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
-};
-use num_bigint::BigInt;
-use rand::Rng;
+use ark_ff::{Field, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
 
 use super::pedersen_commitment::pedersen_commitment;
+use crate::{error::ProofError, transcript::Transcript};
+
+/// Non-interactive proof that the value committed in C_t0 is the product of the two committed factors
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZkMulProof<G: AffineRepr> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_a: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_s: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t0: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t1: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub c_t2: G,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub l_u: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub r_u: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub t_u: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub pi_lr: G::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::hex"))]
+    pub pi_t: G::ScalarField,
+}
 
-pub fn commit_polynomials(
-    l_x: &DensePolynomial<F>,
-    r_x: &DensePolynomial<F>,
-    g_vec: &Vec<G1Affine>,
-) -> (Vec<G1Affine>, Vec<F>) {
-    assert!(
-        l_x.coeffs.len() == 2,
-        "Do not support polynomials with degree higher than 2"
-    );
-    assert!(
-        r_x.coeffs.len() == 2,
-        "Do not support polynomials with degree higher than 2"
-    );
+impl<G: AffineRepr> ZkMulProof<G> {
+    pub fn committments(&self) -> Vec<G> {
+        vec![self.c_a, self.c_s, self.c_t0, self.c_t1, self.c_t2]
+    }
+}
 
-    // t(x) = l(x) * r(x) = (a + s_l*x)(b + s_r*x) = (ab) + (as_r + bs_l)x + (s_l*s_r)x^2
-    let t_x = l_x * r_x;
+pub fn commit_polynomials<G: AffineRepr, R: RngCore + CryptoRng>(
+    l_x: &DensePolynomial<G::ScalarField>,
+    r_x: &DensePolynomial<G::ScalarField>,
+    g_vec: &[G],
+    rng: &mut R,
+) -> Result<(Vec<G>, Vec<G::ScalarField>), ProofError> {
+    // Only linear polynomials l(x) and r(x) are supported
+    for poly in [l_x, r_x] {
+        if poly.coeffs.len() != 2 {
+            return Err(ProofError::UnsupportedDegree(poly.degree()));
+        }
+    }
 
+    // t(x) = l(x) * r(x) = (a + s_l*x)(b + s_r*x) = (ab) + (as_r + bs_l)x + (s_l*s_r)x^2
     // l(x) = a + s_l * x
     let a = l_x.coeffs[0];    // constant term 'a'
     let s_l = l_x.coeffs[1];  // coefficient of x: 's_l'
@@ -38,36 +61,51 @@ pub fn commit_polynomials(
     let b = r_x.coeffs[0];    // constant term 'b'
     let s_r = r_x.coeffs[1];  // coefficient of x: 's_r'
 
-    let alpha = generate_random_field_element();
+    let alpha = generate_random_field_element(rng);
     // C_a = Com(a,b; α) = g_1^a * g_2^b * h^α
-    let _a = pedersen_commitment(&[a, b].to_vec(), g_vec, alpha.clone()).unwrap();
+    let _a = pedersen_commitment(&[a, b], g_vec, alpha)?;
 
-    let beta = generate_random_field_element();
+    let beta = generate_random_field_element(rng);
     // C_s = Com(s_l,s_r; β) = g_1^s_l * g_2^s_r * h^β
-    let _s = pedersen_commitment(&[s_l, s_r].to_vec(), g_vec, beta.clone()).unwrap();
+    let _s = pedersen_commitment(&[s_l, s_r], g_vec, beta)?;
 
-    let tau_0 = generate_random_field_element();
+    let tau_0 = generate_random_field_element(rng);
     // C_t0 = Com(a*b; τ_0) = g_1^(ab) * h^τ_0
-    let _t_0 = pedersen_commitment(&[a * b].to_vec(), g_vec, tau_0.clone()).unwrap();
+    let _t_0 = pedersen_commitment(&[a * b], g_vec, tau_0)?;
 
-    let tau_1 = generate_random_field_element();
+    let tau_1 = generate_random_field_element(rng);
     // C_t1 = Com(a*s_r + b*s_l; τ_1) = g_1^(as_r + bs_l) * h^τ_1
-    let _t_1 = pedersen_commitment(&[a * s_r + b * s_l].to_vec(), g_vec, tau_1.clone()).unwrap();
+    let _t_1 = pedersen_commitment(&[a * s_r + b * s_l], g_vec, tau_1)?;
 
-    let tau_2 = generate_random_field_element();
+    let tau_2 = generate_random_field_element(rng);
     // C_t2 = Com(s_r*s_l; τ_2) = g_1^(s_r*s_l) * h^τ_2
-    let _t_2 = pedersen_commitment(&[s_r * s_l].to_vec(), g_vec, tau_2.clone()).unwrap();
+    let _t_2 = pedersen_commitment(&[s_r * s_l], g_vec, tau_2)?;
 
     // Return all commitments and their blinding factors
-    (
+    Ok((
         [_a, _s, _t_0, _t_1, _t_2].to_vec(),
         [alpha, beta, tau_0, tau_1, tau_2].to_vec(),
-    )
+    ))
 }
 
-pub fn generate_proof(blinding_factors: &Vec<F>, u: &F) -> (F, F) {
-    let [alpha, beta, tau_0, tau_1, tau_2] = blinding_factors.as_slice() else {
-        panic!("Expected exactly 5 blinding factors");
+/// Absorbs C_a, C_s and C_t0..C_t2 into the transcript and derives the evaluation point u from them
+pub fn generate_challenge<G: AffineRepr>(transcript: &mut Transcript, committments: &[G]) -> Result<G::ScalarField, ProofError> {
+    let [_a, _s, _t_0, _t_1, _t_2] = committments else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: committments.len() });
+    };
+
+    transcript.domain_separator(b"zk_mul");
+    transcript.append_point(b"C_a", _a);
+    transcript.append_point(b"C_s", _s);
+    transcript.append_point(b"C_t0", _t_0);
+    transcript.append_point(b"C_t1", _t_1);
+    transcript.append_point(b"C_t2", _t_2);
+    Ok(transcript.challenge_scalar(b"u"))
+}
+
+pub fn generate_proof<F: Field>(blinding_factors: &[F], u: &F) -> Result<(F, F), ProofError> {
+    let [alpha, beta, tau_0, tau_1, tau_2] = blinding_factors else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: blinding_factors.len() });
     };
 
     // π_lr = α + β*u : Linear combination of blinding factors for l(u) and r(u)
@@ -75,18 +113,18 @@ pub fn generate_proof(blinding_factors: &Vec<F>, u: &F) -> (F, F) {
     // π_t = τ_0 + τ_1*u + τ_2*u^2 : Evaluation of blinding polynomial at point u
     let pi_t = *tau_0 + *tau_1 * u + *tau_2 * u * u;
 
-    (pi_lr, pi_t)
+    Ok((pi_lr, pi_t))
 }
 
-pub fn verify_proof(
-    committments: &Vec<G1Affine>,
-    g_vec: &Vec<G1Affine>,
-    proofs: &(F, F),
-    u: &F,
-    poly_evaluation: &(F, F, F),
-) -> bool {
-    let [_a, _s, _t_0, _t_1, _t_2] = committments.as_slice() else {
-        panic!("Expected exactly 5 blinding factors");
+pub fn verify_proof<G: AffineRepr>(
+    committments: &[G],
+    g_vec: &[G],
+    proofs: &(G::ScalarField, G::ScalarField),
+    u: &G::ScalarField,
+    poly_evaluation: &(G::ScalarField, G::ScalarField, G::ScalarField),
+) -> Result<(), ProofError> {
+    let [_a, _s, _t_0, _t_1, _t_2] = committments else {
+        return Err(ProofError::LengthMismatch { expected: 5, found: committments.len() });
     };
 
     let (pi_lr, pi_t) = proofs;
@@ -95,24 +133,77 @@ pub fn verify_proof(
     // Check 1: C_a + u*C_s = Com(l(u),r(u); π_lr)
     // Verifies the commitment to the polynomial evaluations
     let lhs_1 = (*_a + *_s * u).into_affine();
-    let rhs_1 = pedersen_commitment(&[*l_u, *r_u].to_vec(), g_vec, *pi_lr).unwrap();
+    let rhs_1 = pedersen_commitment(&[*l_u, *r_u], g_vec, *pi_lr)?;
 
     // Check 2: Com(t(u); π_t) = C_t0 + u*C_t1 + u^2*C_t2
     // Verifies the commitment to the product polynomial evaluation
-    let lhs_2 = pedersen_commitment(&[*t_u].to_vec(), g_vec, *pi_t).unwrap();
+    let lhs_2 = pedersen_commitment(&[*t_u], g_vec, *pi_t)?;
     let rhs_2 = (*_t_0 + *_t_1 * u + *_t_2 * u * u).into_affine();
 
     // Check 3: t(u) = l(u) * r(u)
     // Verifies that the claimed polynomial evaluations satisfy the multiplication
     let lhs_3 = *t_u;
-    let rhs_3 = l_u * r_u;
+    let rhs_3 = *l_u * r_u;
 
     // All three checks must pass for verification to succeed
-    (lhs_1 == rhs_1) && (lhs_2 == rhs_2) && (lhs_3 == rhs_3)
+    if (lhs_1 != rhs_1) || (lhs_2 != rhs_2) || (lhs_3 != rhs_3) {
+        return Err(ProofError::VerificationFailure);
+    }
+    Ok(())
+}
+
+/// Proves that a * b is the value committed in C_t0, blinding l(x) = a + s_l*x and r(x) = b + s_r*x with fresh
+/// s_l, s_r and deriving the evaluation point u from the transcript
+pub fn prove_mul<G: AffineRepr, R: RngCore + CryptoRng>(
+    a: &G::ScalarField,
+    b: &G::ScalarField,
+    g_vec: &[G],
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> Result<ZkMulProof<G>, ProofError> {
+    let s_l: G::ScalarField = generate_random_field_element(rng);
+    let s_r: G::ScalarField = generate_random_field_element(rng);
+
+    let l_x = DensePolynomial::from_coefficients_vec(vec![*a, s_l]);
+    let r_x = DensePolynomial::from_coefficients_vec(vec![*b, s_r]);
+
+    let (committments, blinding_factors) = commit_polynomials(&l_x, &r_x, g_vec, rng)?;
+    let u = generate_challenge(transcript, &committments)?;
+    let (pi_lr, pi_t) = generate_proof(&blinding_factors, &u)?;
+
+    // l(u) = a + s_l*u, r(u) = b + s_r*u, t(u) = l(u) * r(u)
+    let l_u = *a + s_l * u;
+    let r_u = *b + s_r * u;
+
+    Ok(ZkMulProof {
+        c_a: committments[0],
+        c_s: committments[1],
+        c_t0: committments[2],
+        c_t1: committments[3],
+        c_t2: committments[4],
+        l_u,
+        r_u,
+        t_u: l_u * r_u,
+        pi_lr,
+        pi_t,
+    })
+}
+
+/// Verifies a ZkMulProof, re-deriving the evaluation point u from the committments in the proof
+pub fn verify_mul<G: AffineRepr>(proof: &ZkMulProof<G>, g_vec: &[G], transcript: &mut Transcript) -> Result<(), ProofError> {
+    let committments = proof.committments();
+    let u = generate_challenge(transcript, &committments)?;
+
+    verify_proof(
+        &committments,
+        g_vec,
+        &(proof.pi_lr, proof.pi_t),
+        &u,
+        &(proof.l_u, proof.r_u, proof.t_u),
+    )
 }
 
-pub fn generate_random_field_element() -> F {
-    let mut rng = rand::thread_rng();
-    let num = rng.gen_range(1..100000);
-    F::from(num)
+/// Samples a blinding factor uniformly from the whole scalar field
+pub fn generate_random_field_element<F: PrimeField, R: RngCore + CryptoRng>(rng: &mut R) -> F {
+    F::rand(rng)
 }
@@ -0,0 +1,179 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::{PrimeField, UniformRand};
+    use bulletproofs_rs::{
+        error::ProofError,
+        gadgets::{
+            and, bit_decomposition, boolean, conditional_select, equal, is_equal, is_zero, less_than, or,
+            range, xor,
+        },
+        pedersen_commitment::DeriveGenerators,
+        r1cs::{ConstraintSystem, LinearCombination, Prover, Variable, Verifier},
+        transcript::Transcript,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    type Circuit<S = F> = fn(&mut dyn ConstraintSystem<S>, &[Variable<S>]) -> Result<(), ProofError>;
+
+    fn prove_and_verify(values: &[u64], circuit: Circuit) -> bool {
+        prove_and_verify_on::<G1Affine>(values, circuit)
+    }
+
+    /// Commits to the values, builds the circuit for the prover and the verifier and checks the proof.
+    /// A circuit the prover cannot satisfy counts as not verifying.
+    fn prove_and_verify_on<G: DeriveGenerators>(values: &[u64], circuit: Circuit<G::ScalarField>) -> bool {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut transcript = Transcript::new(b"gadgets_test");
+        let mut prover = Prover::<G>::new(&mut transcript);
+        let (commitments, variables): (Vec<_>, Vec<_>) = values
+            .iter()
+            .map(|v| prover.commit(G::ScalarField::from(*v), G::ScalarField::rand(&mut rng)))
+            .unzip();
+        let proof = match circuit(&mut prover, &variables).and_then(|_| prover.prove(&mut rng)) {
+            Ok(proof) => proof,
+            Err(_) => return false,
+        };
+
+        let mut transcript = Transcript::new(b"gadgets_test");
+        let mut verifier = Verifier::<G>::new(&mut transcript);
+        let variables: Vec<_> = commitments.iter().map(|c| verifier.commit(*c)).collect();
+        circuit(&mut verifier, &variables).unwrap();
+        verifier.verify(&proof).is_ok()
+    }
+
+    fn lc<S: PrimeField>(variable: Variable<S>) -> LinearCombination<S> {
+        variable.into()
+    }
+
+    #[test]
+    fn test_boolean() {
+        let circuit: Circuit = |cs, v| {
+            boolean(cs, lc(v[0]));
+            Ok(())
+        };
+        assert!(prove_and_verify(&[0], circuit));
+        assert!(prove_and_verify(&[1], circuit));
+        assert!(!prove_and_verify(&[2], circuit));
+    }
+
+    #[test]
+    fn test_bit_decomposition() {
+        // v = 0b1011 and its third bit is 0
+        let circuit: Circuit = |cs, v| {
+            let bits = bit_decomposition(cs, lc(v[0]), 4)?;
+            cs.constrain(bits[2].into());
+            Ok(())
+        };
+        assert!(prove_and_verify(&[11], circuit));
+        assert!(!prove_and_verify(&[15], circuit));
+    }
+
+    #[test]
+    fn test_range() {
+        let circuit: Circuit = |cs, v| range(cs, lc(v[0]), 8);
+        assert!(prove_and_verify(&[0], circuit));
+        assert!(prove_and_verify(&[255], circuit));
+        assert!(!prove_and_verify(&[256], circuit));
+    }
+
+    #[test]
+    fn test_is_zero() {
+        // v[1] = is_zero(v[0])
+        let circuit: Circuit = |cs, v| {
+            let result = is_zero(cs, lc(v[0]))?;
+            cs.constrain(result - v[1]);
+            Ok(())
+        };
+        assert!(prove_and_verify(&[0, 1], circuit));
+        assert!(prove_and_verify(&[7, 0], circuit));
+        assert!(!prove_and_verify(&[0, 0], circuit));
+        assert!(!prove_and_verify(&[7, 1], circuit));
+    }
+
+    #[test]
+    fn test_equality() {
+        let circuit: Circuit = |cs, v| {
+            equal(cs, lc(v[0]), lc(v[1]));
+            Ok(())
+        };
+        assert!(prove_and_verify(&[42, 42], circuit));
+        assert!(!prove_and_verify(&[42, 43], circuit));
+
+        // v[2] = (v[0] == v[1])
+        let circuit: Circuit = |cs, v| {
+            let result = is_equal(cs, lc(v[0]), lc(v[1]))?;
+            cs.constrain(result - v[2]);
+            Ok(())
+        };
+        assert!(prove_and_verify(&[42, 42, 1], circuit));
+        assert!(prove_and_verify(&[42, 43, 0], circuit));
+        assert!(!prove_and_verify(&[42, 43, 1], circuit));
+    }
+
+    #[test]
+    fn test_less_than() {
+        // v[2] = (v[0] < v[1]) for 8 bit values
+        let circuit: Circuit = |cs, v| {
+            let result = less_than(cs, lc(v[0]), lc(v[1]), 8)?;
+            cs.constrain(result - v[2]);
+            Ok(())
+        };
+        assert!(prove_and_verify(&[3, 200, 1], circuit));
+        assert!(prove_and_verify(&[0, 255, 1], circuit));
+        assert!(prove_and_verify(&[200, 3, 0], circuit));
+        assert!(prove_and_verify(&[5, 5, 0], circuit));
+        assert!(!prove_and_verify(&[5, 5, 1], circuit));
+        assert!(!prove_and_verify(&[3, 200, 0], circuit));
+    }
+
+    #[test]
+    fn test_less_than_bn254() {
+        let circuit: Circuit<ark_bn254::Fr> = |cs, v| {
+            let result = less_than(cs, lc(v[0]), lc(v[1]), 8)?;
+            cs.constrain(result - v[2]);
+            Ok(())
+        };
+        assert!(prove_and_verify_on::<ark_bn254::G1Affine>(&[3, 200, 1], circuit));
+        assert!(prove_and_verify_on::<ark_bn254::G1Affine>(&[200, 3, 0], circuit));
+        assert!(!prove_and_verify_on::<ark_bn254::G1Affine>(&[3, 200, 0], circuit));
+    }
+
+    #[test]
+    fn test_boolean_logic() {
+        // v[2] = v[0] AND v[1], v[3] = v[0] OR v[1], v[4] = v[0] XOR v[1]
+        let circuit: Circuit = |cs, v| {
+            boolean(cs, lc(v[0]));
+            boolean(cs, lc(v[1]));
+            let a = and(cs, lc(v[0]), lc(v[1]));
+            let o = or(cs, lc(v[0]), lc(v[1]));
+            let x = xor(cs, lc(v[0]), lc(v[1]));
+            cs.constrain(a - v[2]);
+            cs.constrain(o - v[3]);
+            cs.constrain(x - v[4]);
+            Ok(())
+        };
+        for a in 0..2 {
+            for b in 0..2 {
+                assert!(prove_and_verify(&[a, b, a & b, a | b, a ^ b], circuit));
+            }
+        }
+        assert!(!prove_and_verify(&[1, 1, 1, 1, 1], circuit));
+        assert!(!prove_and_verify(&[1, 0, 0, 0, 1], circuit));
+    }
+
+    #[test]
+    fn test_conditional_select() {
+        // v[3] = v[0] ? v[1] : v[2]
+        let circuit: Circuit = |cs, v| {
+            boolean(cs, lc(v[0]));
+            let result = conditional_select(cs, lc(v[0]), lc(v[1]), lc(v[2]));
+            cs.constrain(result - v[3]);
+            Ok(())
+        };
+        assert!(prove_and_verify(&[1, 10, 20, 10], circuit));
+        assert!(prove_and_verify(&[0, 10, 20, 20], circuit));
+        assert!(!prove_and_verify(&[0, 10, 20, 10], circuit));
+        assert!(!prove_and_verify(&[2, 10, 20, 30], circuit));
+    }
+}
@@ -0,0 +1,76 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::UniformRand;
+    use bulletproofs_rs::{
+        interval_proof::{interval_bit_size, prove_interval, verify_interval},
+        range_proof::RangeProofGenerators,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_interval_proof() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for v in [18, 42, 120] {
+            let (proof, c_v) = prove_interval::<G1Affine, _>(v, F::rand(&mut rng), 18, 120, &mut rng).unwrap();
+            assert!(verify_interval(&c_v, &proof, 18, 120).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_interval_proof_negative_bounds() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let min = -(1 << 31);
+        let max = (1 << 31) - 1;
+        for v in [min, -1, 0, max] {
+            let (proof, c_v) = prove_interval::<G1Affine, _>(v, F::rand(&mut rng), min, max, &mut rng).unwrap();
+            assert!(verify_interval(&c_v, &proof, min, max).is_ok());
+        }
+
+        let (proof, c_v) = prove_interval::<G1Affine, _>(i64::MIN, F::rand(&mut rng), i64::MIN, i64::MAX, &mut rng).unwrap();
+        assert!(verify_interval(&c_v, &proof, i64::MIN, i64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_interval_proof_commitment() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let gamma = F::from(99);
+        let (_, c_v) = prove_interval::<G1Affine, _>(-5, gamma, -10, 10, &mut rng).unwrap();
+
+        let gens = RangeProofGenerators::<G1Affine>::new(8);
+        assert_eq!(c_v, gens.commit(&F::from(-5), &gamma));
+    }
+
+    #[test]
+    fn test_interval_proof_out_of_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(prove_interval::<G1Affine, _>(17, F::rand(&mut rng), 18, 120, &mut rng).is_err());
+        assert!(prove_interval::<G1Affine, _>(121, F::rand(&mut rng), 18, 120, &mut rng).is_err());
+        assert!(prove_interval::<G1Affine, _>(5, F::rand(&mut rng), 10, 0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_interval_proof_wrong_interval() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_interval::<G1Affine, _>(50, F::rand(&mut rng), 18, 120, &mut rng).unwrap();
+        assert!(verify_interval(&c_v, &proof, 18, 119).is_err());
+        assert!(verify_interval(&c_v, &proof, 51, 120).is_err());
+    }
+
+    #[test]
+    fn test_interval_bit_size() {
+        assert_eq!(interval_bit_size(18, 120).unwrap(), 8);
+        assert_eq!(interval_bit_size(0, 255).unwrap(), 8);
+        assert_eq!(interval_bit_size(0, 256).unwrap(), 16);
+        assert_eq!(interval_bit_size(-(1 << 31), (1 << 31) - 1).unwrap(), 32);
+        assert_eq!(interval_bit_size(i64::MIN, i64::MAX).unwrap(), 64);
+        assert!(interval_bit_size(1, 0).is_err());
+    }
+
+    #[test]
+    fn test_interval_proof_bn254() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_interval::<ark_bn254::G1Affine, _>(-5, ark_bn254::Fr::rand(&mut rng), -10, 10, &mut rng).unwrap();
+        assert!(verify_interval(&c_v, &proof, -10, 10).is_ok());
+        assert!(verify_interval(&c_v, &proof, -4, 10).is_err());
+    }
+}
@@ -1,25 +1,211 @@
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{AdditiveGroup, Field, UniformRand};
+use ark_ff::{Field, UniformRand};
 use bulletproofs_rs::{
-    log_ipa_proof::{fold_field, fold_points, log_ipa_proof},
+    error::ProofError,
+    log_ipa_proof::{
+        batch_verify, commit_inner_product, compute_l_r, compute_secondary_diagonal, fold_field, fold_points, prove,
+        verification_scalars, verify, InnerProductProof, IpaStatement,
+    },
     random_ec_points::generate_n_random_points,
+    transcript::Transcript,
 };
 
-use ark_bn254::{Fr as F, G1Affine};
+use ark_bn254::{g1, Fr as F, G1Affine, G1Projective};
 use rand::thread_rng;
 
 #[test]
 fn test_log_ipa() {
-    let mut a = vec![F::from(2), F::from(3), F::from(7), F::from(6)];
-    let mut b = vec![F::from(5), F::from(4), F::from(9), F::from(1)];
+    let a = vec![F::from(2), F::from(3), F::from(7), F::from(6)];
+    let b = vec![F::from(5), F::from(4), F::from(9), F::from(1)];
 
-    let mut g_vec = generate_n_random_points("hello".to_string(), 4);
-    let mut h_vec = generate_n_random_points("bullet".to_string(), 4);
-    let q = generate_n_random_points("proof".to_string(), 1)[0];
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), 4);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
 
-    let verification = log_ipa_proof(a, b, g_vec, h_vec, q);
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
 
-    assert!(verification)
+    // The verifier only knows the generators and P
+    let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    let verification = verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof);
+
+    assert!(verification.is_ok())
+}
+
+#[test]
+fn test_compute_l_r() {
+    let a = vec![F::from(2), F::from(3)];
+    let b = vec![F::from(5), F::from(4)];
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 2);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), 2);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let (l_com, r_com) = compute_l_r(a.clone(), b.clone(), g_vec.clone(), h_vec.clone(), &q).unwrap();
+
+    // a and g fold with u and u^-1, b and h with u^-1 and u, so L takes b_1·h_0 and R takes b_0·h_1
+    let expected_l = (g_vec[1] * a[0] + h_vec[0] * b[1] + q * (a[0] * b[1])).into_affine();
+    let expected_r = (g_vec[0] * a[1] + h_vec[1] * b[0] + q * (a[1] * b[0])).into_affine();
+    assert_eq!(l_com, expected_l);
+    assert_eq!(r_com, expected_r);
+}
+
+#[test]
+fn test_log_ipa_proof_size() {
+    let mut rng = thread_rng();
+    let n: usize = 16;
+    let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+    let b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n as i32);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), n as i32);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+
+    assert_eq!(proof.l_vec.len(), 4);
+    assert_eq!(proof.r_vec.len(), 4);
+
+    let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_ok());
+}
+
+#[test]
+fn test_log_ipa_wrong_commitment() {
+    let a = vec![F::from(2), F::from(3), F::from(7), F::from(6)];
+    let b = vec![F::from(5), F::from(4), F::from(9), F::from(1)];
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), 4);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+
+    // P for a claimed inner product that is off by one
+    let p = (commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap() + q).into_affine();
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_err());
+}
+
+#[test]
+fn test_log_ipa_tampered_proof() {
+    let a = vec![F::from(2), F::from(3), F::from(7), F::from(6)];
+    let b = vec![F::from(5), F::from(4), F::from(9), F::from(1)];
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), 4);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let mut proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+    proof.l_vec.swap(0, 1);
+
+    let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_err());
+}
+
+#[test]
+fn test_verification_scalars() {
+    let mut rng = thread_rng();
+    let n: usize = 8;
+    let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+    let b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n as i32);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), n as i32);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+    let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    let (challenges, challenges_inv, s) = verification_scalars(&mut verifier_transcript, n, &p, &proof).unwrap();
+
+    // Folding the generators round by round ends in Σ_i s_i * g_i and Σ_i s_i^-1 * h_i
+    let mut g_prime = g_vec.clone();
+    let mut h_prime = h_vec.clone();
+    for (u, u_inv) in challenges.iter().zip(challenges_inv.iter()) {
+        g_prime = fold_points(g_prime, u_inv).unwrap();
+        h_prime = fold_points(h_prime, u).unwrap();
+    }
+    let g_folded: G1Projective = g_vec.iter().zip(s.iter()).map(|(g, s_i)| *g * s_i).sum();
+    let h_folded: G1Projective = h_vec.iter().zip(s.iter()).map(|(h, s_i)| *h * s_i.inverse().unwrap()).sum();
+    assert_eq!(g_prime[0], g_folded.into_affine());
+    assert_eq!(h_prime[0], h_folded.into_affine());
+}
+
+#[test]
+fn test_log_ipa_large() {
+    let mut rng = thread_rng();
+    let n: usize = 256;
+    let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+    let b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n as i32);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), n as i32);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut prover_transcript = Transcript::new(b"test_log_ipa");
+    let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+
+    let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+    let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+    assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_ok());
+}
+
+#[test]
+fn test_batch_verify() {
+    let mut rng = thread_rng();
+    let n: usize = 8;
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n as i32);
+    let h_vec = generate_n_random_points::<g1::Config>("bullet".to_string(), n as i32);
+    let q = generate_n_random_points::<g1::Config>("proof".to_string(), 1)[0];
+
+    let mut proofs = Vec::new();
+    let mut commitments = Vec::new();
+    for _ in 0..6 {
+        let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let b: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let mut prover_transcript = Transcript::new(b"test_log_ipa");
+        proofs.push(prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap());
+        commitments.push(commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap());
+    }
+
+    fn statements<'a>(
+        proofs: &'a [InnerProductProof<G1Affine>],
+        commitments: &[G1Affine],
+        g_vec: &'a [G1Affine],
+        h_vec: &'a [G1Affine],
+        q: G1Affine,
+    ) -> Vec<IpaStatement<'a, G1Affine>> {
+        proofs
+            .iter()
+            .zip(commitments.iter())
+            .map(|(proof, p)| IpaStatement {
+                transcript: Transcript::new(b"test_log_ipa"),
+                g_vec,
+                h_vec,
+                q,
+                p: *p,
+                proof,
+            })
+            .collect()
+    }
+    assert_eq!(batch_verify(&statements(&proofs, &commitments, &g_vec, &h_vec, q), &mut rng), Ok(()));
+
+    // Tampering with the second and fifth statement is caught and reported
+    commitments[1] = (commitments[1] + q).into_affine();
+    proofs[4].a += F::from(1);
+    assert_eq!(
+        batch_verify(&statements(&proofs, &commitments, &g_vec, &h_vec, q), &mut rng),
+        Err(ProofError::BatchVerificationFailure(vec![1, 4]))
+    );
 }
 
 #[test]
@@ -36,7 +222,7 @@ fn test_fold_field() {
     ];
     let original_input = input.clone();
 
-    let result = fold_field(input, &u);
+    let result = fold_field(input, &u).unwrap();
 
     // Check length is (original_length + 1) / 2
     assert_eq!(result.len(), 2);
@@ -66,7 +252,7 @@ fn test_fold_points() {
     ];
     let original_input = input.clone();
 
-    let result = fold_points(input, &u);
+    let result = fold_points(input, &u).unwrap();
 
     // Check length is (original_length + 1) / 2
     assert_eq!(result.len(), 2);
@@ -82,3 +268,26 @@ fn test_fold_points() {
     assert_eq!(result[1], expected_second);
 
 }
+
+#[test]
+fn test_compute_secondary_diagonal() {
+    let mut rng = thread_rng();
+    let n: usize = 64;
+    let a: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+    let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n as i32);
+
+    let (l_com, r_com) = compute_secondary_diagonal(a.clone(), g_vec.clone()).unwrap();
+
+    // L = Σ_i a_2i * g_2i+1 and R = Σ_i a_2i+1 * g_2i
+    let expected_l: G1Projective = (0..n / 2).map(|i| g_vec[2 * i + 1] * a[2 * i]).sum();
+    let expected_r: G1Projective = (0..n / 2).map(|i| g_vec[2 * i] * a[2 * i + 1]).sum();
+    assert_eq!(l_com, expected_l.into_affine());
+    assert_eq!(r_com, expected_r.into_affine());
+
+    // Folding a longer vector matches the element-wise definition
+    let u = F::rand(&mut rng);
+    let folded = fold_points(g_vec.clone(), &u).unwrap();
+    for (i, point) in folded.iter().enumerate() {
+        assert_eq!(*point, (g_vec[2 * i] * u + g_vec[2 * i + 1] * u.inverse().unwrap()).into_affine());
+    }
+}
@@ -0,0 +1,67 @@
+mod test {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+    use bulletproofs_rs::{
+        error::ProofError,
+        log_ipa_proof, msm::msm, pedersen_commitment, random_ec_points, succinct_proof, vector_polynomial::Coeff,
+    };
+
+    /// Σ_i scalars_i * bases_i accumulated point by point
+    fn naive_commit<G: AffineRepr>(scalars: &[G::ScalarField], bases: &[G]) -> G {
+        scalars
+            .iter()
+            .zip(bases.iter())
+            .fold(G::zero(), |acc, (scalar, base)| (acc + *base * scalar).into_affine())
+    }
+
+    #[test]
+    fn test_msm() {
+        use ark_bls12_381::Fr as F;
+
+        let mut rng = ark_std::test_rng();
+        for n in [0usize, 1, 2, 17, 64] {
+            let bases = pedersen_commitment::generate_n_random_points::<ark_bls12_381::g1::Config>("msm".to_string(), n as i32);
+            let scalars: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+            assert_eq!(msm(&scalars, &bases).unwrap().into_affine(), naive_commit(&scalars, &bases));
+        }
+    }
+
+    #[test]
+    fn test_commit_functions_match_naive_commit() {
+        use ark_bls12_381::Fr as F;
+
+        let mut rng = ark_std::test_rng();
+        for n in [1usize, 5, 32] {
+            let bases = pedersen_commitment::generate_n_random_points::<ark_bls12_381::g1::Config>("msm".to_string(), n as i32);
+            let scalars: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+            let expected = naive_commit(&scalars, &bases);
+
+            assert_eq!(pedersen_commitment::commit(&scalars, &bases).unwrap(), expected);
+            assert_eq!(log_ipa_proof::commit(&scalars, &bases).unwrap(), expected);
+            assert_eq!(Coeff::from_slice(&scalars).commit(&bases).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_bn254_commit_functions_match_naive_commit() {
+        use ark_bn254::Fr as F;
+
+        let mut rng = ark_std::test_rng();
+        for n in [1usize, 5, 32] {
+            let bases = random_ec_points::generate_n_random_points::<ark_bn254::g1::Config>("msm".to_string(), n as i32);
+            let scalars: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+            let expected = naive_commit(&scalars, &bases);
+
+            assert_eq!(succinct_proof::commit(&scalars, &bases).unwrap(), expected);
+            assert_eq!(log_ipa_proof::commit(&scalars, &bases).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_msm_invalid_lengths() {
+        use ark_bls12_381::Fr as F;
+
+        let bases = pedersen_commitment::generate_n_random_points::<ark_bls12_381::g1::Config>("msm".to_string(), 2);
+        assert_eq!(msm(&[F::from(1)], &bases), Err(ProofError::LengthMismatch { expected: 2, found: 1 }));
+    }
+}
@@ -1,26 +1,47 @@
 mod test {
-    use ark_bls12_381::{Bls12_381, Fq, Fr as F};
+    use ark_bls12_381::{g1, Fr as F};
+    use ark_ec::CurveGroup;
     use ark_ff::UniformRand;
     use bulletproofs_rs::pedersen_commitment::{generate_n_random_points, pedersen_commitment};
 
     #[test]
     fn test_generate_n_points() {
-        let points = generate_n_random_points("hello".to_string(), 10);
+        let points = generate_n_random_points::<g1::Config>("hello".to_string(), 10);
         for point in points {
             assert!(point.is_on_curve())
         }
     }
 
+    #[test]
+    fn test_generated_points_are_in_prime_order_subgroup() {
+        let points = generate_n_random_points::<g1::Config>("hello".to_string(), 10);
+        for point in points {
+            assert!(point.is_in_correct_subgroup_assuming_on_curve())
+        }
+    }
+
     #[test]
     fn test_pedersen_committment() {
         let mut rng = ark_std::test_rng();
         let vector = [1, 2, 3, 4, 5];
-        let field_element_vector: Vec<F> = vector.into_iter().map(|val| F::from(val)).collect();
+        let field_element_vector: Vec<F> = vector.into_iter().map(F::from).collect();
         let g_vec =
-            generate_n_random_points("hello".to_string(), (field_element_vector.len() + 1) as i32);
+            generate_n_random_points::<g1::Config>("hello".to_string(), (field_element_vector.len() + 1) as i32);
         let blinding_factor = F::rand(&mut rng);
         let committment = pedersen_commitment(&field_element_vector, &g_vec, blinding_factor);
         dbg!(&committment);
         assert!(committment.is_ok())
     }
+
+    #[test]
+    fn test_pedersen_committment_bn254() {
+        use ark_bn254::Fr;
+
+        let g_vec = generate_n_random_points::<ark_bn254::g1::Config>("hello".to_string(), 3);
+        for point in &g_vec {
+            assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve())
+        }
+        let committment = pedersen_commitment(&[Fr::from(1), Fr::from(2)], &g_vec, Fr::from(3)).unwrap();
+        assert_eq!(committment, (g_vec[0] + g_vec[1] * Fr::from(2) + g_vec[2] * Fr::from(3)).into_affine());
+    }
 }
@@ -0,0 +1,178 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::{PrimeField, UniformRand};
+    use bulletproofs_rs::{
+        error::ProofError,
+        pedersen_commitment::DeriveGenerators,
+        r1cs::{ConstraintSystem, Prover, R1csProof, Variable, Verifier},
+        transcript::Transcript,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// x * y = z
+    fn multiplication_gadget<CS: ConstraintSystem<F>>(cs: &mut CS, x: Variable<F>, y: Variable<F>, z: Variable<F>) {
+        let (_, _, o) = cs.multiply(x.into(), y.into());
+        cs.constrain(o - z);
+    }
+
+    fn prove_multiplication(
+        x: u64,
+        y: u64,
+        z: u64,
+    ) -> Result<(R1csProof<G1Affine>, Vec<G1Affine>), Box<dyn std::error::Error>> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut prover = Prover::<G1Affine>::new(&mut transcript);
+
+        let (c_x, x) = prover.commit(F::from(x), F::rand(&mut rng));
+        let (c_y, y) = prover.commit(F::from(y), F::rand(&mut rng));
+        let (c_z, z) = prover.commit(F::from(z), F::rand(&mut rng));
+        multiplication_gadget(&mut prover, x, y, z);
+
+        Ok((prover.prove(&mut rng)?, vec![c_x, c_y, c_z]))
+    }
+
+    fn verify_multiplication(proof: &R1csProof<G1Affine>, commitments: &[G1Affine]) -> bool {
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut verifier = Verifier::<G1Affine>::new(&mut transcript);
+
+        let x = verifier.commit(commitments[0]);
+        let y = verifier.commit(commitments[1]);
+        let z = verifier.commit(commitments[2]);
+        multiplication_gadget(&mut verifier, x, y, z);
+
+        verifier.verify(proof).is_ok()
+    }
+
+    #[test]
+    fn test_r1cs_multiplication() {
+        let (proof, commitments) = prove_multiplication(3, 7, 21).unwrap();
+        assert!(verify_multiplication(&proof, &commitments));
+    }
+
+    #[test]
+    fn test_r1cs_unsatisfied_constraint() {
+        assert!(prove_multiplication(3, 7, 22).is_err());
+    }
+
+    #[test]
+    fn test_r1cs_wrong_commitment() {
+        let (proof, mut commitments) = prove_multiplication(3, 7, 21).unwrap();
+        commitments.swap(0, 2);
+        assert!(!verify_multiplication(&proof, &commitments));
+    }
+
+    #[test]
+    fn test_r1cs_tampered_proof() {
+        let (proof, commitments) = prove_multiplication(3, 7, 21).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.t_x += F::from(1);
+        assert!(!verify_multiplication(&tampered, &commitments));
+
+        let mut tampered = proof;
+        tampered.c_ao = tampered.c_ai;
+        assert!(!verify_multiplication(&tampered, &commitments));
+    }
+
+    #[test]
+    fn test_r1cs_different_circuit() {
+        let (proof, commitments) = prove_multiplication(3, 7, 21).unwrap();
+
+        // x * y = z + 1 instead of x * y = z
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut verifier = Verifier::<G1Affine>::new(&mut transcript);
+        let x = verifier.commit(commitments[0]);
+        let y = verifier.commit(commitments[1]);
+        let z = verifier.commit(commitments[2]);
+        let (_, _, o) = verifier.multiply(x.into(), y.into());
+        verifier.constrain(o - z - F::from(1));
+
+        assert!(verifier.verify(&proof).is_err());
+    }
+
+    /// x^3 + x + 5 = out with x allocated as a secret wire
+    fn cubic_gadget<S: PrimeField, CS: ConstraintSystem<S>>(cs: &mut CS, x: Option<S>, out: Variable<S>) {
+        let x = cs.allocate(x).unwrap();
+        let (_, _, x_squared) = cs.multiply(x.into(), x.into());
+        let (_, _, x_cubed) = cs.multiply(x_squared.into(), x.into());
+        cs.constrain(x_cubed + x + S::from(5) - out);
+    }
+
+    fn prove_and_verify_cubic<G: DeriveGenerators>() -> bool {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut prover = Prover::<G>::new(&mut transcript);
+        let (c_out, out) = prover.commit(G::ScalarField::from(35), G::ScalarField::rand(&mut rng));
+        cubic_gadget(&mut prover, Some(G::ScalarField::from(3)), out);
+        let proof = prover.prove(&mut rng).unwrap();
+
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut verifier = Verifier::<G>::new(&mut transcript);
+        let out = verifier.commit(c_out);
+        cubic_gadget(&mut verifier, None, out);
+        verifier.verify(&proof).is_ok()
+    }
+
+    #[test]
+    fn test_r1cs_cubic() {
+        assert!(prove_and_verify_cubic::<G1Affine>());
+    }
+
+    #[test]
+    fn test_r1cs_cubic_bn254() {
+        assert!(prove_and_verify_cubic::<ark_bn254::G1Affine>());
+    }
+
+    #[test]
+    fn test_r1cs_linear_constraints() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // a + 2*b = c without any multiplication gate
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut prover = Prover::<G1Affine>::new(&mut transcript);
+        let (c_a, a) = prover.commit(F::from(4), F::rand(&mut rng));
+        let (c_b, b) = prover.commit(F::from(5), F::rand(&mut rng));
+        let (c_c, c) = prover.commit(F::from(14), F::rand(&mut rng));
+        prover.constrain(a + b * F::from(2) - c);
+        let proof = prover.prove(&mut rng).unwrap();
+
+        let mut transcript = Transcript::new(b"r1cs_test");
+        let mut verifier = Verifier::<G1Affine>::new(&mut transcript);
+        let a = verifier.commit(c_a);
+        let b = verifier.commit(c_b);
+        let c = verifier.commit(c_c);
+        verifier.constrain(a + b * F::from(2) - c);
+        assert!(verifier.verify(&proof).is_ok());
+    }
+
+    #[test]
+    fn test_r1cs_unknown_variable() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, commitments) = prove_multiplication(3, 7, 21).unwrap();
+
+        // The circuit has one multiplier and three commitments, so these variables do not exist
+        let unknown = [Variable::MultiplierLeft(1), Variable::MultiplierOutput(5), Variable::Committed(3)];
+        for variable in unknown {
+            let mut transcript = Transcript::new(b"r1cs_test");
+            let mut prover = Prover::<G1Affine>::new(&mut transcript);
+            let (_, x) = prover.commit(F::from(3), F::rand(&mut rng));
+            let (_, y) = prover.commit(F::from(7), F::rand(&mut rng));
+            let (_, z) = prover.commit(F::from(21), F::rand(&mut rng));
+            multiplication_gadget(&mut prover, x, y, z);
+            assert_eq!(prover.evaluate(&variable.into()), None);
+            prover.constrain(variable.into());
+            assert_eq!(prover.prove(&mut rng).unwrap_err(), ProofError::UnknownVariable);
+
+            let mut transcript = Transcript::new(b"r1cs_test");
+            let mut verifier = Verifier::<G1Affine>::new(&mut transcript);
+            let x = verifier.commit(commitments[0]);
+            let y = verifier.commit(commitments[1]);
+            let z = verifier.commit(commitments[2]);
+            multiplication_gadget(&mut verifier, x, y, z);
+            verifier.constrain(variable.into());
+            assert_eq!(verifier.verify(&proof).unwrap_err(), ProofError::UnknownVariable);
+        }
+    }
+}
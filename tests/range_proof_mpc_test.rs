@@ -0,0 +1,173 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::{AdditiveGroup, UniformRand};
+    use bulletproofs_rs::{
+        error::ProofError,
+        pedersen_commitment::DeriveGenerators,
+        range_proof::{verify_range_aggregated, RangeProof, RangeProofGenerators},
+        range_proof_mpc::{Dealer, Party, PolyChallenge, ProofShare},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn run_protocol(values: &[u64], n_bits: usize) -> (RangeProof<G1Affine>, Vec<G1Affine>) {
+        run_protocol_on::<G1Affine>(values, n_bits)
+    }
+
+    /// Runs the dealer and one party per value through every round of the protocol
+    fn run_protocol_on<G: DeriveGenerators>(values: &[u64], n_bits: usize) -> (RangeProof<G>, Vec<G>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let dealer = Dealer::new::<G>(n_bits, values.len()).unwrap();
+        let parties: Vec<_> = values
+            .iter()
+            .map(|v| Party::new::<G>(*v, G::ScalarField::rand(&mut rng), n_bits, values.len()).unwrap())
+            .collect();
+
+        let (parties, bit_commitments): (Vec<_>, Vec<_>) = parties
+            .into_iter()
+            .enumerate()
+            .map(|(j, party)| party.assign_position(j, &mut rng).unwrap())
+            .unzip();
+        let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments, &mut rng).unwrap();
+
+        let (parties, poly_commitments): (Vec<_>, Vec<_>) = parties
+            .into_iter()
+            .map(|party| party.apply_challenge(&bit_challenge, &mut rng).unwrap())
+            .unzip();
+        let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments, &mut rng).unwrap();
+
+        let proof_shares: Vec<ProofShare<G>> = parties
+            .into_iter()
+            .map(|party| party.apply_challenge(&poly_challenge).unwrap())
+            .collect();
+        dealer.receive_shares(proof_shares).unwrap()
+    }
+
+    #[test]
+    fn test_mpc_range_proof() {
+        let (proof, commitments) = run_protocol(&[1, 255], 8);
+        assert_eq!(commitments.len(), 2);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_ok());
+
+        let (proof, commitments) = run_protocol(&[0, 7, 1 << 15, u16::MAX as u64], 16);
+        assert!(verify_range_aggregated(&commitments, &proof, 16).is_ok());
+    }
+
+    #[test]
+    fn test_mpc_range_proof_bn254() {
+        let (proof, commitments) = run_protocol_on::<ark_bn254::G1Affine>(&[3, 100, 42], 8);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_ok());
+        assert!(verify_range_aggregated(&commitments[..2], &proof, 8).is_err());
+    }
+
+    #[test]
+    fn test_mpc_range_proof_padding() {
+        let (proof, commitments) = run_protocol(&[3, 100, 42], 8);
+        assert_eq!(commitments.len(), 3);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_ok());
+    }
+
+    #[test]
+    fn test_mpc_range_proof_commitments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let gamma = F::from(777);
+        let party = Party::new::<G1Affine>(42, gamma, 8, 1).unwrap();
+        let (_, bit_commitment) = party.assign_position(0, &mut rng).unwrap();
+
+        let gens = RangeProofGenerators::<G1Affine>::new(8);
+        assert_eq!(bit_commitment.v_j, gens.commit(&F::from(42), &gamma));
+    }
+
+    #[test]
+    fn test_mpc_party_out_of_range() {
+        assert!(Party::new::<G1Affine>(256, F::from(1), 8, 1).is_err());
+        assert!(Party::new::<G1Affine>(3, F::from(1), 4, 1).is_err());
+        assert_eq!(Party::new::<G1Affine>(3, F::from(1), 8, 0).err(), Some(ProofError::EmptyInput));
+    }
+
+    #[test]
+    fn test_mpc_party_rejects_position() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Positions outside of the agreed parties, including ones whose generator offset overflows
+        for position in [2, 1 << 40, usize::MAX / 8, usize::MAX] {
+            let party = Party::new::<G1Affine>(5, F::from(1), 8, 2).unwrap();
+            assert_eq!(
+                party.assign_position(position, &mut rng).err(),
+                Some(ProofError::PositionOutOfRange { position, m: 2 })
+            );
+        }
+
+        // A party that agreed to more positions than fit in the generator offsets fails instead of overflowing
+        let party = Party::new::<G1Affine>(5, F::from(1), 64, usize::MAX).unwrap();
+        let position = usize::MAX / 8;
+        assert_eq!(
+            party.assign_position(position, &mut rng).err(),
+            Some(ProofError::PositionOutOfRange { position, m: usize::MAX })
+        );
+    }
+
+    #[test]
+    fn test_mpc_dealer_rejects_bit_commitments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let parties: Vec<_> = (0..2)
+            .map(|v| Party::new::<G1Affine>(v, F::from(v + 1), 8, 2).unwrap())
+            .collect();
+        let bit_commitments: Vec<_> = parties
+            .into_iter()
+            .enumerate()
+            .map(|(j, party)| party.assign_position(j, &mut rng).unwrap().1)
+            .collect();
+
+        // Missing party
+        let dealer = Dealer::new::<G1Affine>(8, 2).unwrap();
+        assert!(dealer.receive_bit_commitments(bit_commitments[..1].to_vec(), &mut rng).is_err());
+
+        // Positions out of order
+        let dealer = Dealer::new::<G1Affine>(8, 2).unwrap();
+        let reversed = bit_commitments.iter().rev().cloned().collect();
+        assert!(dealer.receive_bit_commitments(reversed, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_mpc_dealer_rejects_tampered_share() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let dealer = Dealer::new::<G1Affine>(8, 2).unwrap();
+        let parties: Vec<_> = [5, 6]
+            .iter()
+            .map(|v| Party::new::<G1Affine>(*v, F::rand(&mut rng), 8, 2).unwrap())
+            .collect();
+
+        let (parties, bit_commitments): (Vec<_>, Vec<_>) = parties
+            .into_iter()
+            .enumerate()
+            .map(|(j, party)| party.assign_position(j, &mut rng).unwrap())
+            .unzip();
+        let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments, &mut rng).unwrap();
+        let (parties, poly_commitments): (Vec<_>, Vec<_>) = parties
+            .into_iter()
+            .map(|party| party.apply_challenge(&bit_challenge, &mut rng).unwrap())
+            .unzip();
+        let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments, &mut rng).unwrap();
+
+        let mut proof_shares: Vec<ProofShare<G1Affine>> = parties
+            .into_iter()
+            .map(|party| party.apply_challenge(&poly_challenge).unwrap())
+            .collect();
+        proof_shares[1].tau_x += F::from(1);
+
+        let err = dealer.receive_shares(proof_shares).unwrap_err();
+        assert!(err.to_string().contains("[1]"));
+    }
+
+    #[test]
+    fn test_mpc_party_rejects_zero_challenge() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let dealer = Dealer::new::<G1Affine>(8, 1).unwrap();
+        let party = Party::new::<G1Affine>(9, F::from(2), 8, 1).unwrap();
+        let (party, bit_commitment) = party.assign_position(0, &mut rng).unwrap();
+        let (_, bit_challenge) = dealer.receive_bit_commitments(vec![bit_commitment], &mut rng).unwrap();
+        let (party, _) = party.apply_challenge(&bit_challenge, &mut rng).unwrap();
+
+        assert!(party.apply_challenge(&PolyChallenge { x: F::ZERO }).is_err());
+    }
+}
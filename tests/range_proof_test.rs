@@ -0,0 +1,213 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use bulletproofs_rs::{
+        error::ProofError,
+        range_proof::{
+            batch_verify, prove_range, prove_range_aggregated, verify_range, verify_range_aggregated,
+            RangeProofGenerators,
+        },
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_range_proof() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for n_bits in [8, 16, 32, 64] {
+            let gamma = F::rand(&mut rng);
+            let (proof, c_v) = prove_range::<G1Affine, _>(37, gamma, n_bits, &mut rng).unwrap();
+            assert!(verify_range(&c_v, &proof, n_bits).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_range_proof_bounds() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let (proof, c_v) = prove_range::<G1Affine, _>(0, F::rand(&mut rng), 8, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 8).is_ok());
+
+        let (proof, c_v) = prove_range::<G1Affine, _>(255, F::rand(&mut rng), 8, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 8).is_ok());
+
+        let (proof, c_v) = prove_range::<G1Affine, _>(u64::MAX, F::rand(&mut rng), 64, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 64).is_ok());
+    }
+
+    #[test]
+    fn test_range_proof_commitment() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let gamma = F::from(12345);
+        let (_, c_v) = prove_range::<G1Affine, _>(42, gamma, 8, &mut rng).unwrap();
+
+        let gens = RangeProofGenerators::<G1Affine>::new(8);
+        assert_eq!(c_v, gens.commit(&F::from(42), &gamma));
+    }
+
+    #[test]
+    fn test_range_proof_seeded() {
+        let gamma = F::from(12345);
+        let (proof, c_v) = prove_range::<G1Affine, _>(42, gamma, 8, &mut StdRng::seed_from_u64(7)).unwrap();
+
+        // Proving with the same seed is deterministic, a different seed gives different blinding factors
+        assert_eq!(prove_range::<G1Affine, _>(42, gamma, 8, &mut StdRng::seed_from_u64(7)).unwrap(), (proof.clone(), c_v));
+        let (other, other_c_v) = prove_range::<G1Affine, _>(42, gamma, 8, &mut StdRng::seed_from_u64(8)).unwrap();
+        assert_eq!(other_c_v, c_v);
+        assert_ne!(other.c_a, proof.c_a);
+        assert_ne!(other.tau_x, proof.tau_x);
+        assert!(verify_range(&c_v, &other, 8).is_ok());
+    }
+
+    #[test]
+    fn test_range_proof_out_of_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(prove_range::<G1Affine, _>(256, F::rand(&mut rng), 8, &mut rng).is_err());
+        assert!(prove_range::<G1Affine, _>(1 << 20, F::rand(&mut rng), 16, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_unsupported_bit_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(prove_range::<G1Affine, _>(3, F::rand(&mut rng), 4, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_wrong_commitment() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<G1Affine, _>(100, F::rand(&mut rng), 8, &mut rng).unwrap();
+
+        // A commitment to a different value
+        let gens = RangeProofGenerators::<G1Affine>::new(8);
+        let c_v_wrong = (c_v + gens.g).into_affine();
+        assert!(verify_range(&c_v_wrong, &proof, 8).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_wrong_bit_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<G1Affine, _>(100, F::rand(&mut rng), 8, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 16).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_tampered() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut proof, c_v) = prove_range::<G1Affine, _>(100, F::rand(&mut rng), 8, &mut rng).unwrap();
+        proof.t_x += F::from(1);
+        assert!(verify_range(&c_v, &proof, 8).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [1, 200, 3000, 40000];
+        let blindings: Vec<F> = values.iter().map(|_| F::rand(&mut rng)).collect();
+
+        let (proof, commitments) = prove_range_aggregated::<G1Affine, _>(&values, &blindings, 16, &mut rng).unwrap();
+        assert_eq!(commitments.len(), 4);
+        assert!(verify_range_aggregated(&commitments, &proof, 16).is_ok());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_padding() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [5, 6, 7];
+        let blindings: Vec<F> = values.iter().map(|_| F::rand(&mut rng)).collect();
+
+        let (proof, commitments) = prove_range_aggregated::<G1Affine, _>(&values, &blindings, 8, &mut rng).unwrap();
+        assert_eq!(commitments.len(), 3);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_ok());
+
+        // Commitments match the ones of the individual values
+        let gens = RangeProofGenerators::<G1Affine>::new(8);
+        for ((v, gamma), commitment) in values.iter().zip(blindings.iter()).zip(commitments.iter()) {
+            assert_eq!(gens.commit(&F::from(*v), gamma), *commitment);
+        }
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [1, 2, 3, 4, 5, 6, 7, 8];
+        let blindings: Vec<F> = values.iter().map(|_| F::rand(&mut rng)).collect();
+
+        let (single, _) = prove_range::<G1Affine, _>(1, blindings[0], 8, &mut rng).unwrap();
+        let (proof, commitments) = prove_range_aggregated::<G1Affine, _>(&values, &blindings, 8, &mut rng).unwrap();
+
+        // 8 values only cost 2*log2(8) additional group elements
+        assert_eq!(proof.ipa_proof.l_vec.len(), single.ipa_proof.l_vec.len() + 3);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_ok());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_out_of_range() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [1, 256];
+        let blindings: Vec<F> = values.iter().map(|_| F::rand(&mut rng)).collect();
+        assert!(prove_range_aggregated::<G1Affine, _>(&values, &blindings, 8, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_invalid_lengths() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(prove_range_aggregated::<G1Affine, _>(&[1, 2], &[F::rand(&mut rng)], 8, &mut rng).is_err());
+        assert!(prove_range_aggregated::<G1Affine, _>(&[], &[], 8, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_wrong_commitments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [10, 20];
+        let blindings: Vec<F> = values.iter().map(|_| F::rand(&mut rng)).collect();
+
+        let (proof, mut commitments) = prove_range_aggregated::<G1Affine, _>(&values, &blindings, 8, &mut rng).unwrap();
+
+        // Commitments in a different order
+        commitments.swap(0, 1);
+        assert!(verify_range_aggregated(&commitments, &proof, 8).is_err());
+
+        // A missing commitment
+        assert!(verify_range_aggregated(&commitments[..1], &proof, 8).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof_1, c_1) = prove_range::<G1Affine, _>(5, F::rand(&mut rng), 16, &mut rng).unwrap();
+        let blindings: Vec<F> = (0..3).map(|_| F::rand(&mut rng)).collect();
+        let (proof_2, c_2) = prove_range_aggregated::<G1Affine, _>(&[1, 2, 3], &blindings, 16, &mut rng).unwrap();
+        let (proof_3, c_3) = prove_range::<G1Affine, _>(40000, F::rand(&mut rng), 16, &mut rng).unwrap();
+        let c_1 = [c_1];
+        let c_3 = [c_3];
+
+        let proofs = [(&c_1[..], &proof_1), (&c_2[..], &proof_2), (&c_3[..], &proof_3)];
+        assert_eq!(batch_verify(&proofs, 16, &mut rng), Ok(()));
+
+        // The third proof checked against the first commitment
+        let proofs = [(&c_1[..], &proof_1), (&c_2[..], &proof_2), (&c_1[..], &proof_3)];
+        assert_eq!(batch_verify(&proofs, 16, &mut rng), Err(ProofError::BatchVerificationFailure(vec![2])));
+
+        let mut tampered = proof_2.clone();
+        tampered.mu += F::from(1);
+        let proofs = [(&c_1[..], &proof_1), (&c_2[..], &tampered), (&c_3[..], &proof_3)];
+        assert_eq!(batch_verify(&proofs, 16, &mut rng), Err(ProofError::BatchVerificationFailure(vec![1])));
+
+        assert_eq!(
+            batch_verify(&[(&c_1[..], &proof_1)], 8, &mut rng),
+            Err(ProofError::BatchVerificationFailure(vec![0]))
+        );
+    }
+
+    #[test]
+    fn test_range_proof_bn254() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let gamma = ark_bn254::Fr::from(12345);
+        let (proof, c_v) = prove_range::<ark_bn254::G1Affine, _>(37, gamma, 16, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 16).is_ok());
+        assert!(verify_range(&c_v, &proof, 8).is_err());
+
+        let gens = RangeProofGenerators::<ark_bn254::G1Affine>::new(16);
+        assert_eq!(c_v, gens.commit(&ark_bn254::Fr::from(37), &gamma));
+    }
+}
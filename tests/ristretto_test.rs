@@ -0,0 +1,209 @@
+#![cfg(feature = "ristretto")]
+
+mod test {
+    use ark_curve25519::Fq;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{AdditiveGroup, Field};
+    use bulletproofs_rs::{
+        error::ProofError,
+        log_ipa_proof::{commit_inner_product, prove, verify},
+        pedersen_commitment::{self, commit},
+        random_ec_points,
+        range_proof::{prove_range, verify_range},
+        ristretto::{compress, decompress, from_uniform_bytes, generate_n_random_points, RistrettoPoint, Scalar},
+        serialization::{from_bytes, to_bytes},
+        transcript::Transcript,
+        vector_polynomial::Coeff,
+        zk_ipa::{prove_zk_ipa, verify_zk_ipa, ZkIpaProof},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Encodings of 0·B to 4·B from RFC 9496, appendix A.1
+    const BASEPOINT_MULTIPLES: [&str; 5] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+    ];
+
+    fn from_hex<const N: usize>(encoded: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..encoded.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    fn ristretto_setup() -> (Vec<RistrettoPoint>, Vec<RistrettoPoint>, RistrettoPoint, RistrettoPoint) {
+        let g_vec = generate_n_random_points("hello".to_string(), 4);
+        let h_vec = generate_n_random_points("bulletproof".to_string(), 4);
+        let g = generate_n_random_points("ios".to_string(), 1)[0];
+        let h = generate_n_random_points("seed".to_string(), 1)[0];
+        (g_vec, h_vec, g, h)
+    }
+
+    /// Runs zk_ipa for random vectors of length four on the curve of the generators
+    fn prove_and_verify_zk_ipa<G: AffineRepr>(g_vec: &[G], h_vec: &[G], g: &G, h: &G) -> Result<(), ProofError> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let a = Coeff::<G::ScalarField>::random(4, &mut rng);
+        let b = Coeff::<G::ScalarField>::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, g_vec, h_vec, g, h, &mut rng, &mut prover_transcript)?;
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        verify_zk_ipa(&c_v, &proof, g_vec, h_vec, g, h, &mut verifier_transcript)
+    }
+
+    #[test]
+    fn test_compress_basepoint_multiples() {
+        let generator = RistrettoPoint::generator();
+        for (k, encoded) in BASEPOINT_MULTIPLES.iter().enumerate() {
+            let point = (generator * Scalar::from(k as u64)).into_affine();
+            assert_eq!(compress(&point), from_hex::<32>(encoded));
+        }
+    }
+
+    #[test]
+    fn test_decompress_roundtrip() {
+        let generator = RistrettoPoint::generator();
+        for (k, encoded) in BASEPOINT_MULTIPLES.iter().enumerate() {
+            let bytes = from_hex::<32>(encoded);
+            let point = decompress(&bytes).unwrap();
+            assert_eq!(point, (generator * Scalar::from(k as u64)).into_affine());
+            assert_eq!(compress(&point), bytes);
+        }
+    }
+
+    #[test]
+    fn test_decompress_rejects_invalid_encodings() {
+        for encoded in [
+            // Negative s
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            // s = p is not canonical
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // Not the encoding of an element
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+        ] {
+            assert_eq!(decompress(&from_hex::<32>(encoded)), Err(ProofError::InvalidEncoding));
+        }
+    }
+
+    #[test]
+    fn test_small_order_component_is_dropped() {
+        let point = generate_n_random_points("hello".to_string(), 1)[0];
+        // (0, -1) has order two on every twisted Edwards curve
+        let torsion = RistrettoPoint::new_unchecked(Fq::ZERO, -Fq::ONE);
+        let shifted = (point + torsion).into_affine();
+        assert!(!shifted.is_in_correct_subgroup_assuming_on_curve());
+
+        // Both points encode the same element, which decodes to the prime order point
+        assert_eq!(compress(&shifted), compress(&point));
+        assert_eq!(decompress(&compress(&shifted)).unwrap(), point);
+
+        // The binary format refuses the point outright
+        let bytes = to_bytes(&shifted).unwrap();
+        assert_eq!(from_bytes::<RistrettoPoint>(&bytes), Err(ProofError::InvalidPoint));
+    }
+
+    #[test]
+    fn test_from_uniform_bytes() {
+        // SHA-512 of "Ristretto is traditionally a short shot of espresso coffee", RFC 9496 appendix A.3
+        let bytes = from_hex::<64>(
+            "5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c1\
+             4d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6",
+        );
+        assert_eq!(
+            compress(&from_uniform_bytes(&bytes)),
+            from_hex::<32>("3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46")
+        );
+    }
+
+    #[test]
+    fn test_generate_n_points() {
+        let points = generate_n_random_points("hello".to_string(), 8);
+        for (i, point) in points.iter().enumerate() {
+            assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+            assert_eq!(decompress(&compress(point)).unwrap(), *point);
+            assert!(points[..i].iter().all(|other| other != point));
+        }
+        assert_eq!(generate_n_random_points("hello".to_string(), 8), points);
+    }
+
+    #[test]
+    fn test_commit_ristretto() {
+        let (g_vec, _, _, _) = ristretto_setup();
+        let a = [Scalar::from(1), Scalar::from(2), Scalar::from(3), Scalar::from(4)];
+        let expected = (g_vec[0] * a[0] + g_vec[1] * a[1] + g_vec[2] * a[2] + g_vec[3] * a[3]).into_affine();
+        assert_eq!(commit(&a, &g_vec).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_log_ipa_ristretto() {
+        let (g_vec, h_vec, q, _) = ristretto_setup();
+        let a = vec![Scalar::from(2), Scalar::from(3), Scalar::from(7), Scalar::from(6)];
+        let b = vec![Scalar::from(5), Scalar::from(4), Scalar::from(9), Scalar::from(1)];
+
+        let mut prover_transcript = Transcript::new(b"test_log_ipa");
+        let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+
+        let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+        let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+        assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_ok());
+
+        let wrong_p = (p + q).into_affine();
+        let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+        assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &wrong_p, &proof).is_err());
+    }
+
+    #[test]
+    fn test_zk_ipa_ristretto_proof_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (g_vec, h_vec, g, h) = ristretto_setup();
+        let a = Coeff::<Scalar>::random(4, &mut rng);
+        let b = Coeff::<Scalar>::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+
+        let decoded: ZkIpaProof<RistrettoPoint> = from_bytes(&to_bytes(&proof).unwrap()).unwrap();
+        assert_eq!(decoded, proof);
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v, &decoded, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_ok());
+
+        // C_v committing to <a, b> + 1
+        let c_v_wrong = (c_v + g).into_affine();
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v_wrong, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_ristretto() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<RistrettoPoint, _>(37, Scalar::from(12345), 16, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 16).is_ok());
+        assert!(verify_range(&c_v, &proof, 8).is_err());
+    }
+
+    #[test]
+    fn test_zk_ipa_across_backends() {
+        let (g_vec, h_vec, g, h) = ristretto_setup();
+        assert!(prove_and_verify_zk_ipa(&g_vec, &h_vec, &g, &h).is_ok());
+
+        type Bls12_381 = ark_bls12_381::g1::Config;
+        let g_vec = pedersen_commitment::generate_n_random_points::<Bls12_381>("hello".to_string(), 4);
+        let h_vec = pedersen_commitment::generate_n_random_points::<Bls12_381>("bulletproof".to_string(), 4);
+        let g = pedersen_commitment::generate_n_random_points::<Bls12_381>("ios".to_string(), 1)[0];
+        let h = pedersen_commitment::generate_n_random_points::<Bls12_381>("seed".to_string(), 1)[0];
+        assert!(prove_and_verify_zk_ipa(&g_vec, &h_vec, &g, &h).is_ok());
+
+        type Bn254 = ark_bn254::g1::Config;
+        let g_vec = random_ec_points::generate_n_random_points::<Bn254>("hello".to_string(), 4);
+        let h_vec = random_ec_points::generate_n_random_points::<Bn254>("bulletproof".to_string(), 4);
+        let g = random_ec_points::generate_n_random_points::<Bn254>("ios".to_string(), 1)[0];
+        let h = random_ec_points::generate_n_random_points::<Bn254>("seed".to_string(), 1)[0];
+        assert!(prove_and_verify_zk_ipa(&g_vec, &h_vec, &g, &h).is_ok());
+    }
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "secp256k1")]
+
+mod test {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use bulletproofs_rs::{
+        log_ipa_proof::{commit_inner_product, prove, verify},
+        pedersen_commitment::{pedersen_commitment, DeriveGenerators},
+        range_proof::{prove_range, prove_range_aggregated, verify_range, verify_range_aggregated, RangeProofGenerators},
+        secp256k1::{
+            generate_n_random_points, generator_h, lift_x, nums_points, serialize_uncompressed, Scalar,
+            Secp256k1Point,
+        },
+        serialization::{from_bytes, to_bytes},
+        transcript::Transcript,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The generator H of libsecp256k1-zkp and Elements
+    const H_X: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+    const H_Y: &str = "31d3c6863973926e049e637cb1b5f40a36dac28af1766968c30c2313f3a38904";
+
+    fn from_hex<const N: usize>(encoded: &str) -> [u8; N] {
+        let bytes: Vec<u8> = (0..encoded.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).unwrap())
+            .collect();
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    fn test_generator_h() {
+        let h = generator_h();
+        let encoded = serialize_uncompressed(&h).unwrap();
+        assert_eq!(encoded[1..33], from_hex::<32>(H_X));
+        assert_eq!(encoded[33..], from_hex::<32>(H_Y));
+        assert!(h.is_on_curve() && h.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(lift_x(&from_hex::<32>(H_X)), Some(h));
+    }
+
+    #[test]
+    fn test_serialize_generator() {
+        let encoded = serialize_uncompressed(&Secp256k1Point::generator()).unwrap();
+        assert_eq!(encoded[0], 0x04);
+        assert_eq!(encoded[1..33], from_hex::<32>("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"));
+        assert!(serialize_uncompressed(&Secp256k1Point::zero()).is_err());
+    }
+
+    #[test]
+    fn test_lift_x() {
+        // 2^256 - 1 is above the field modulus
+        assert_eq!(lift_x(&[0xff; 32]), None);
+        // x = 5 gives y² = 132, which is not a square modulo p
+        let mut five = [0u8; 32];
+        five[31] = 5;
+        assert_eq!(lift_x(&five), None);
+
+        let point = lift_x(&from_hex::<32>(H_X)).unwrap();
+        assert!(point.y().unwrap().into_bigint().is_even());
+    }
+
+    #[test]
+    fn test_nums_points() {
+        let points = nums_points(b"hello", 8);
+        for (i, point) in points.iter().enumerate() {
+            assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+            assert!(points[..i].iter().all(|other| other != point));
+        }
+        assert_eq!(nums_points(b"hello", 8), points);
+        assert_eq!(nums_points(b"hello", 3), points[..3]);
+        assert_eq!(generate_n_random_points("hello".to_string(), 8), points);
+    }
+
+    #[test]
+    fn test_pedersen_commitment_secp256k1() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (h, g) = Secp256k1Point::pedersen_generators();
+        assert_eq!((h, g), (generator_h(), Secp256k1Point::generator()));
+
+        // C = v·H + r·G as in confidential transactions
+        let v = Scalar::from(1_000_000u64);
+        let r = Scalar::rand(&mut rng);
+        let c = pedersen_commitment(&[v], &[h, g], r).unwrap();
+        assert_eq!(c, (h * v + g * r).into_affine());
+        assert_eq!(RangeProofGenerators::<Secp256k1Point>::new(8).commit(&v, &r), c);
+
+        let decoded: Secp256k1Point = from_bytes(&to_bytes(&c).unwrap()).unwrap();
+        assert_eq!(decoded, c);
+    }
+
+    #[test]
+    fn test_log_ipa_secp256k1() {
+        let g_vec = generate_n_random_points("hello".to_string(), 4);
+        let h_vec = generate_n_random_points("bulletproof".to_string(), 4);
+        let q = generate_n_random_points("ios".to_string(), 1)[0];
+        let a = vec![Scalar::from(2), Scalar::from(3), Scalar::from(7), Scalar::from(6)];
+        let b = vec![Scalar::from(5), Scalar::from(4), Scalar::from(9), Scalar::from(1)];
+
+        let mut prover_transcript = Transcript::new(b"test_log_ipa");
+        let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &a, &b).unwrap();
+
+        let p = commit_inner_product(&g_vec, &h_vec, &q, &a, &b).unwrap();
+        let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+        assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_ok());
+
+        let wrong_p = (p + q).into_affine();
+        let mut verifier_transcript = Transcript::new(b"test_log_ipa");
+        assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &wrong_p, &proof).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_secp256k1() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let gamma = Scalar::rand(&mut rng);
+        let (proof, c_v) = prove_range::<Secp256k1Point, _>(21_000_000, gamma, 32, &mut rng).unwrap();
+        assert!(verify_range(&c_v, &proof, 32).is_ok());
+        assert_eq!(c_v, (generator_h() * Scalar::from(21_000_000u64) + Secp256k1Point::generator() * gamma).into_affine());
+
+        let wrong_c_v = (c_v + generator_h()).into_affine();
+        assert!(verify_range(&wrong_c_v, &proof, 32).is_err());
+        assert!(prove_range::<Secp256k1Point, _>(256, gamma, 8, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof_secp256k1() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = [1, 200, 3000, 40000];
+        let blindings: Vec<Scalar> = values.iter().map(|_| Scalar::rand(&mut rng)).collect();
+
+        let (proof, mut commitments) =
+            prove_range_aggregated::<Secp256k1Point, _>(&values, &blindings, 16, &mut rng).unwrap();
+        assert!(verify_range_aggregated(&commitments, &proof, 16).is_ok());
+
+        commitments.swap(0, 1);
+        assert!(verify_range_aggregated(&commitments, &proof, 16).is_err());
+    }
+}
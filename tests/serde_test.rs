@@ -0,0 +1,134 @@
+#![cfg(feature = "serde")]
+
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use bulletproofs_rs::{
+        pedersen_commitment::generate_n_random_points,
+        range_proof::{prove_range, verify_range, RangeProof},
+        range_proof_mpc::ProofShare,
+        transcript::Transcript,
+        vector_polynomial::{Coeff, VectorPolynomial},
+        zk_ipa::{prove_zk_ipa, verify_zk_ipa, ZkIpaProof},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    /// A JSON document that embeds a commitment next to the proof
+    #[derive(Serialize, Deserialize)]
+    struct RangeProofRequest {
+        #[serde(with = "bulletproofs_rs::serialization::hex")]
+        commitment: G1Affine,
+        proof: RangeProof<G1Affine>,
+        n_bits: usize,
+    }
+
+    fn zk_ipa_setup() -> (Vec<G1Affine>, Vec<G1Affine>, G1Affine, G1Affine) {
+        let g_vec = generate_n_random_points("hello".to_string(), 4);
+        let h_vec = generate_n_random_points("bulletproof".to_string(), 4);
+        let g = generate_n_random_points("ios".to_string(), 1)[0];
+        let h = generate_n_random_points("seed".to_string(), 1)[0];
+        (g_vec, h_vec, g, h)
+    }
+
+    #[test]
+    fn test_zk_ipa_proof_json_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (g_vec, h_vec, g, h) = zk_ipa_setup();
+        let (proof, c_v) = prove_zk_ipa(
+            &Coeff::random(4, &mut rng),
+            &Coeff::random(4, &mut rng),
+            &g_vec,
+            &h_vec,
+            &g,
+            &h,
+            &mut rng,
+            &mut Transcript::new(b"test"),
+        ).unwrap();
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: ZkIpaProof<G1Affine> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(verify_zk_ipa(&c_v, &decoded, &g_vec, &h_vec, &g, &h, &mut Transcript::new(b"test")).is_ok());
+    }
+
+    #[test]
+    fn test_hex_encoding() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<G1Affine, _>(42, F::from(5), 8, &mut rng).unwrap();
+        let request = RangeProofRequest { commitment: c_v, proof, n_bits: 8 };
+        let json: Value = serde_json::to_value(&request).unwrap();
+
+        // Compressed points take 48 bytes and scalars 32 bytes, as lowercase hex
+        let commitment = json["commitment"].as_str().unwrap();
+        assert_eq!(commitment.len(), 96);
+        assert!(commitment.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        assert_eq!(json["proof"]["t_x"].as_str().unwrap().len(), 64);
+        assert_eq!(json["proof"]["ipa_proof"]["l_vec"].as_array().unwrap().len(), 3);
+        assert_eq!(json["n_bits"], 8);
+
+        let decoded: RangeProofRequest = serde_json::from_value(json).unwrap();
+        assert!(verify_range(&decoded.commitment, &decoded.proof, decoded.n_bits).is_ok());
+    }
+
+    #[test]
+    fn test_coeff_and_share_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let coeff = Coeff::random(3, &mut rng);
+        let json = serde_json::to_value(&coeff).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(serde_json::from_value::<Coeff<F>>(json).unwrap(), coeff);
+
+        let share = ProofShare {
+            position: 1,
+            t_x: F::from(1),
+            tau_x: F::from(2),
+            mu: F::from(3),
+            l_vec: Coeff::random(2, &mut rng),
+            r_vec: Coeff::random(2, &mut rng),
+        };
+        let json = serde_json::to_string(&share).unwrap();
+        assert_eq!(serde_json::from_str::<ProofShare<G1Affine>>(&json).unwrap(), share);
+    }
+
+    #[test]
+    fn test_vector_polynomial_widths() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let poly = VectorPolynomial::new(vec![Coeff::<F>::random(2, &mut rng), Coeff::random(2, &mut rng)]).unwrap();
+        let json = serde_json::to_value(&poly).unwrap();
+        let decoded = serde_json::from_value::<VectorPolynomial<F>>(json.clone()).unwrap();
+        assert_eq!(decoded.coeffs(), poly.coeffs());
+
+        let mut uneven = json;
+        uneven["coeffs"][1].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<VectorPolynomial<F>>(uneven).is_err());
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<G1Affine, _>(42, F::from(5), 8, &mut rng).unwrap();
+        let request = RangeProofRequest { commitment: c_v, proof, n_bits: 8 };
+        let json = serde_json::to_value(&request).unwrap();
+
+        let with_commitment = |commitment: &str| {
+            let mut json = json.clone();
+            json["commitment"] = Value::String(commitment.to_string());
+            serde_json::from_value::<RangeProofRequest>(json)
+        };
+        let encoded = json["commitment"].as_str().unwrap().to_string();
+        assert!(with_commitment(&encoded).is_ok());
+
+        // Not hex, odd length, truncated and trailing bytes
+        assert!(with_commitment(&encoded.replace(&encoded[..2], "zz")).is_err());
+        assert!(with_commitment(&encoded[1..]).is_err());
+        assert!(with_commitment(&encoded[..94]).is_err());
+        assert!(with_commitment(&format!("{encoded}00")).is_err());
+
+        // A changed x coordinate is off the curve or outside the prime order subgroup
+        let mut bytes = hex::decode(&encoded).unwrap();
+        bytes[47] ^= 1;
+        let off_curve = hex::encode(bytes);
+        assert!(with_commitment(&off_curve).is_err());
+    }
+}
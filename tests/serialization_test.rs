@@ -0,0 +1,218 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use bulletproofs_rs::{
+        error::ProofError,
+        log_ipa_proof::{self, InnerProductProof},
+        pedersen_commitment::generate_n_random_points,
+        polynomial_commitment::commit_polynomial,
+        r1cs::{ConstraintSystem, Prover, R1csProof},
+        random_ec_points,
+        range_proof::{prove_range, RangeProof},
+        serialization::{from_bytes, to_bytes, MAGIC, VERSION},
+        transcript::Transcript,
+        vector_polynomial::{Coeff, VectorPolynomial},
+        zk_ipa::{prove_zk_ipa, ZkIpaProof},
+        zk_mul::{prove_mul, ZkMulProof},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn roundtrip<T>(value: &T) -> Vec<u8>
+    where
+        T: CanonicalSerialize + CanonicalDeserialize + PartialEq + std::fmt::Debug,
+    {
+        let bytes = to_bytes(value).unwrap();
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(bytes[4], VERSION);
+        assert_eq!(&from_bytes::<T>(&bytes).unwrap(), value);
+        bytes
+    }
+
+    fn inner_product_proof() -> InnerProductProof<G1Affine> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("hello".to_string(), 8);
+        let h_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("bullet".to_string(), 8);
+        let q = generate_n_random_points::<ark_bls12_381::g1::Config>("proof".to_string(), 1)[0];
+        let a: Vec<F> = (0..8).map(|_| F::rand(&mut rng)).collect();
+        let b: Vec<F> = (0..8).map(|_| F::rand(&mut rng)).collect();
+        log_ipa_proof::prove(&mut Transcript::new(b"test"), &g_vec, &h_vec, &q, &a, &b).unwrap()
+    }
+
+    #[test]
+    fn test_inner_product_proof_roundtrip() {
+        let proof = inner_product_proof();
+        let bytes = roundtrip(&proof);
+
+        // 5 header bytes, two length prefixes, 3 rounds of compressed 48 byte L and R, and two 32 byte scalars
+        assert_eq!(bytes.len(), 5 + 2 * 8 + 6 * 48 + 2 * 32);
+    }
+
+    #[test]
+    fn test_bn254_inner_product_proof_roundtrip() {
+        use ark_bn254::Fr;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = random_ec_points::generate_n_random_points::<ark_bn254::g1::Config>("hello".to_string(), 4);
+        let h_vec = random_ec_points::generate_n_random_points::<ark_bn254::g1::Config>("bullet".to_string(), 4);
+        let q = random_ec_points::generate_n_random_points::<ark_bn254::g1::Config>("proof".to_string(), 1)[0];
+        let a: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        let b: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+        let proof = log_ipa_proof::prove(&mut Transcript::new(b"test"), &g_vec, &h_vec, &q, &a, &b).unwrap();
+        roundtrip(&proof);
+    }
+
+    #[test]
+    fn test_zk_mul_proof_roundtrip() {
+        let g_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let proof = prove_mul(&F::from(7), &F::from(9), &g_vec, &mut rng, &mut Transcript::new(b"test")).unwrap();
+
+        let bytes = roundtrip(&proof);
+        assert!(from_bytes::<ZkIpaProof<G1Affine>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_zk_ipa_proof_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("hello".to_string(), 4);
+        let h_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("bulletproof".into(), 4);
+        let g = generate_n_random_points::<ark_bls12_381::g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<ark_bls12_381::g1::Config>("seed".to_string(), 1)[0];
+
+        let (proof, c_v) = prove_zk_ipa(
+            &Coeff::random(4, &mut rng),
+            &Coeff::random(4, &mut rng),
+            &g_vec,
+            &h_vec,
+            &g,
+            &h,
+            &mut rng,
+            &mut Transcript::new(b"test"),
+        ).unwrap();
+        roundtrip(&proof);
+        roundtrip(&c_v);
+    }
+
+    #[test]
+    fn test_range_and_r1cs_proof_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (proof, c_v) = prove_range::<G1Affine, _>(42, F::from(5), 8, &mut rng).unwrap();
+        let bytes = roundtrip(&proof);
+        assert_eq!(from_bytes::<RangeProof<G1Affine>>(&bytes).unwrap(), proof);
+        roundtrip(&c_v);
+
+        let mut transcript = Transcript::new(b"test");
+        let mut prover = Prover::<G1Affine>::new(&mut transcript);
+        let (_, x) = prover.commit(F::from(3), F::from(1));
+        let (_, _, o) = prover.multiply(x.into(), x.into());
+        prover.constrain(o - F::from(9));
+        let proof: R1csProof<G1Affine> = prover.prove(&mut rng).unwrap();
+        roundtrip(&proof);
+    }
+
+    #[test]
+    fn test_polynomial_commitment_roundtrip() {
+        let g = generate_n_random_points::<ark_bls12_381::g1::Config>("ios".to_string(), 1)[0];
+        let b = generate_n_random_points::<ark_bls12_381::g1::Config>("seed".to_string(), 1)[0];
+        let poly = DensePolynomial::from_coefficients_vec(vec![F::from(1), F::from(2), F::from(3)]);
+        let gammas = vec![F::from(4), F::from(5), F::from(6)];
+
+        let commitments: Vec<G1Affine> = commit_polynomial(&poly, &gammas, &g, &b).unwrap();
+        roundtrip(&commitments);
+    }
+
+    #[test]
+    fn test_vector_polynomial_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let coeff = Coeff::<F>::random(5, &mut rng);
+        roundtrip(&coeff);
+
+        let poly = VectorPolynomial::new(vec![Coeff::random(3, &mut rng), Coeff::zero(3), Coeff::one(3)]).unwrap();
+        let bytes = to_bytes(&poly).unwrap();
+        let decoded: VectorPolynomial<F> = from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.coeffs(), poly.coeffs());
+
+        // Coefficient vectors of different widths encode like a polynomial but are rejected
+        let bytes = to_bytes(&vec![Coeff::<F>::random(3, &mut rng), Coeff::random(2, &mut rng)]).unwrap();
+        assert!(from_bytes::<VectorPolynomial<F>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let proof = inner_product_proof();
+        let bytes = to_bytes(&proof).unwrap();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] ^= 1;
+        assert_eq!(
+            from_bytes::<InnerProductProof<G1Affine>>(&wrong_magic),
+            Err(ProofError::InvalidEncoding)
+        );
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = VERSION + 1;
+        assert_eq!(
+            from_bytes::<InnerProductProof<G1Affine>>(&wrong_version),
+            Err(ProofError::InvalidEncoding)
+        );
+
+        assert!(from_bytes::<InnerProductProof<G1Affine>>(&bytes[..3]).is_err());
+        assert!(from_bytes::<InnerProductProof<G1Affine>>(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(from_bytes::<InnerProductProof<G1Affine>>(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_oversized_length_prefix() {
+        let with_length = |len: u64| [&MAGIC[..], &[VERSION], &len.to_le_bytes()].concat();
+
+        // A length prefix far beyond the remaining input fails without reserving memory for it
+        for len in [u64::MAX, 1 << 40, 2] {
+            let bytes = with_length(len);
+            assert_eq!(from_bytes::<InnerProductProof<G1Affine>>(&bytes), Err(ProofError::InvalidEncoding));
+            assert_eq!(from_bytes::<Coeff<F>>(&bytes), Err(ProofError::InvalidEncoding));
+            assert_eq!(from_bytes::<VectorPolynomial<F>>(&bytes).unwrap_err(), ProofError::InvalidEncoding);
+        }
+
+        // The R vector of a valid proof declares more points than follow it
+        let proof = inner_product_proof();
+        let mut bytes = to_bytes(&proof).unwrap();
+        let r_len = 5 + 8 + 48 * proof.l_vec.len();
+        bytes[r_len..r_len + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(from_bytes::<InnerProductProof<G1Affine>>(&bytes), Err(ProofError::InvalidEncoding));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let (range_proof, _) = prove_range::<G1Affine, _>(42, F::from(5), 8, &mut rng).unwrap();
+        let mut bytes = to_bytes(&range_proof).unwrap();
+        let l_len = 5 + 4 * 48 + 3 * 32;
+        bytes[l_len..l_len + 8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert_eq!(from_bytes::<RangeProof<G1Affine>>(&bytes), Err(ProofError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_invalid_point() {
+        let proof = inner_product_proof();
+        let mut bytes = to_bytes(&proof).unwrap();
+
+        // Corrupt the x coordinate of the first L, after the header and the length prefix
+        bytes[5 + 8 + 10] ^= 0xff;
+        assert!(from_bytes::<InnerProductProof<G1Affine>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_zk_mul_proof_fields() {
+        let g_vec = generate_n_random_points::<ark_bls12_381::g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let proof = prove_mul(&F::from(2), &F::from(3), &g_vec, &mut rng, &mut Transcript::new(b"test")).unwrap();
+
+        // Five compressed points and five scalars, without any length prefix
+        assert_eq!(proof.compressed_size(), 5 * 48 + 5 * 32);
+        let decoded = ZkMulProof::deserialize_compressed(&to_bytes(&proof).unwrap()[5..]).unwrap();
+        assert_eq!(decoded, proof);
+    }
+}
@@ -0,0 +1,114 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ff::UniformRand;
+    use bulletproofs_rs::shuffle_proof::{prove_shuffle, verify_shuffle};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn random_blindings(k: usize) -> Vec<F> {
+        let mut rng = StdRng::seed_from_u64(0);
+        (0..k).map(|_| F::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_shuffle_proof() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for k in [1, 2, 3, 8] {
+            let inputs: Vec<F> = (0..k as u64).map(|i| F::from(i * 7 + 3)).collect();
+            let outputs: Vec<F> = inputs.iter().rev().copied().collect();
+
+            let (proof, c_inputs, c_outputs) = prove_shuffle::<G1Affine, _>(
+                &inputs,
+                &random_blindings(k),
+                &outputs,
+                &random_blindings(k),
+                &mut rng,
+            )
+            .unwrap();
+            assert!(verify_shuffle(c_inputs, c_outputs, &proof).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_shuffle_proof_repeated_values() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inputs = vec![F::from(1), F::from(1), F::from(2), F::from(5)];
+        let outputs = vec![F::from(5), F::from(1), F::from(2), F::from(1)];
+
+        let (proof, c_inputs, c_outputs) = prove_shuffle::<G1Affine, _>(
+            &inputs,
+            &random_blindings(4),
+            &outputs,
+            &random_blindings(4),
+            &mut rng,
+        )
+        .unwrap();
+        assert!(verify_shuffle(c_inputs, c_outputs, &proof).is_ok());
+    }
+
+    #[test]
+    fn test_shuffle_proof_not_a_permutation() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inputs = vec![F::from(1), F::from(2), F::from(3)];
+        let outputs = vec![F::from(3), F::from(2), F::from(2)];
+        assert!(prove_shuffle::<G1Affine, _>(
+            &inputs,
+            &random_blindings(3),
+            &outputs,
+            &random_blindings(3),
+            &mut rng,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_shuffle_proof_wrong_commitments() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inputs = vec![F::from(10), F::from(20), F::from(30)];
+        let outputs = vec![F::from(30), F::from(10), F::from(20)];
+        let (proof, c_inputs, c_outputs) = prove_shuffle::<G1Affine, _>(
+            &inputs,
+            &random_blindings(3),
+            &outputs,
+            &random_blindings(3),
+            &mut rng,
+        )
+        .unwrap();
+
+        // Exchanging inputs and outputs changes the transcript
+        assert!(verify_shuffle(c_outputs.clone(), c_inputs.clone(), &proof).is_err());
+
+        let mut tampered = c_outputs.clone();
+        tampered[0] = c_inputs[0];
+        assert!(verify_shuffle(c_inputs.clone(), tampered, &proof).is_err());
+
+        assert!(verify_shuffle(c_inputs[..2].to_vec(), c_outputs[..2].to_vec(), &proof).is_err());
+    }
+
+    #[test]
+    fn test_shuffle_proof_invalid_lengths() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values = vec![F::from(1), F::from(2)];
+        assert!(prove_shuffle::<G1Affine, _>(
+            &values,
+            &random_blindings(2),
+            &values[..1],
+            &random_blindings(1),
+            &mut rng,
+        )
+        .is_err());
+        assert!(prove_shuffle::<G1Affine, _>(&[], &[], &[], &[], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_shuffle_proof_bn254() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inputs: Vec<ark_bn254::Fr> = (0..4).map(|i| ark_bn254::Fr::from(i * 7 + 3)).collect();
+        let outputs: Vec<ark_bn254::Fr> = inputs.iter().rev().copied().collect();
+        let blindings: Vec<ark_bn254::Fr> = (0..4).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+
+        let (proof, c_inputs, c_outputs) =
+            prove_shuffle::<ark_bn254::G1Affine, _>(&inputs, &blindings, &outputs, &blindings, &mut rng).unwrap();
+        assert!(verify_shuffle(c_inputs.clone(), c_outputs.clone(), &proof).is_ok());
+        assert!(verify_shuffle(c_outputs, c_inputs, &proof).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+mod test {
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ec::AffineRepr;
+    use bulletproofs_rs::transcript::Transcript;
+
+    #[test]
+    fn test_challenges_are_deterministic() {
+        let mut prover = Transcript::new(b"test");
+        let mut verifier = Transcript::new(b"test");
+
+        prover.append_point(b"G", &G1Affine::generator());
+        verifier.append_point(b"G", &G1Affine::generator());
+
+        let u_prover: F = prover.challenge_scalar(b"u");
+        let u_verifier: F = verifier.challenge_scalar(b"u");
+
+        assert_eq!(u_prover, u_verifier);
+    }
+
+    #[test]
+    fn test_challenges_depend_on_messages() {
+        let mut first = Transcript::new(b"test");
+        let mut second = Transcript::new(b"test");
+
+        first.append_scalar(b"x", &F::from(1));
+        second.append_scalar(b"x", &F::from(2));
+
+        let u_first: F = first.challenge_scalar(b"u");
+        let u_second: F = second.challenge_scalar(b"u");
+
+        assert_ne!(u_first, u_second);
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut first = Transcript::new(b"protocol_a");
+        let mut second = Transcript::new(b"protocol_b");
+
+        let u_first: F = first.challenge_scalar(b"u");
+        let u_second: F = second.challenge_scalar(b"u");
+
+        assert_ne!(u_first, u_second);
+    }
+
+    #[test]
+    fn test_consecutive_challenges_differ() {
+        let mut transcript = Transcript::new(b"test");
+
+        let u_1: F = transcript.challenge_scalar(b"u");
+        let u_2: F = transcript.challenge_scalar(b"u");
+
+        assert_ne!(u_1, u_2);
+    }
+}
@@ -1,12 +1,21 @@
 #[cfg(test)]
 mod test {
     use ark_bls12_381::Fr as F;
-    use ark_ff::{AdditiveGroup, Field};
-    use bulletproofs_rs::vector_polynomial::{Coeff, InnerProduct, VectorPolynomial};
+    use ark_ff::{AdditiveGroup, PrimeField};
+    use ark_poly::Polynomial;
+    use ark_ec::CurveGroup;
+    use bulletproofs_rs::{
+        error::ProofError,
+        log_ipa_proof::{commit_inner_product, prove, verify},
+        random_ec_points::generate_n_random_points,
+        transcript::Transcript,
+        vector_polynomial::{Coeff, InnerProduct, VectorPolynomial},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_coeff_zero() {
-        let zero_coeff = Coeff::zero(3);
+        let zero_coeff = Coeff::<F>::zero(3);
         assert_eq!(zero_coeff.0.len(), 3);
         assert!(zero_coeff.0.iter().all(|x| x.eq(&F::ZERO)));
     }
@@ -15,7 +24,7 @@ mod test {
     fn test_coeff_addition() {
         let a = Coeff(vec![F::from(1u64), F::from(2u64), F::from(3u64)]);
         let b = Coeff(vec![F::from(4u64), F::from(5u64), F::from(6u64)]);
-        let sum = a.clone() + b;
+        let sum = a.checked_add(&b).unwrap();
         assert_eq!(sum.0, vec![F::from(5u64), F::from(7u64), F::from(9u64)]);
     }
 
@@ -23,30 +32,42 @@ mod test {
     fn test_coeff_multiplication() {
         let a = Coeff(vec![F::from(1u64), F::from(2u64), F::from(3u64)]);
         let b = Coeff(vec![F::from(4u64), F::from(5u64), F::from(6u64)]);
-        let product = a.clone() * b;
+        let product = a.checked_mul(&b).unwrap();
 
         let result = Coeff(vec![F::from(4u64), F::from(10u64), F::from(18u64)]);
         assert_eq!(product, result);
     }
 
+    #[test]
+    fn test_coeff_length_mismatch() {
+        let a = Coeff(vec![F::from(1u64), F::from(2u64), F::from(3u64)]);
+        let b = Coeff(vec![F::from(4u64), F::from(5u64)]);
+        let mismatch = ProofError::LengthMismatch { expected: 3, found: 2 };
+        assert_eq!(a.checked_add(&b).unwrap_err(), mismatch);
+        assert_eq!(a.checked_mul(&b).unwrap_err(), mismatch);
+        assert_eq!(a.inner_product(&b).unwrap_err(), mismatch);
+    }
+
     #[test]
     fn test_vector_polynomial_creation() {
         let coeffs = vec![
             Coeff(vec![F::from(1u64), F::from(2u64)]),
             Coeff(vec![F::from(3u64), F::from(4u64)]),
         ];
-        let poly = VectorPolynomial::new(coeffs);
-        assert_eq!(poly.coeffs.len(), 2);
+        let poly = VectorPolynomial::new(coeffs).unwrap();
+        assert_eq!(poly.coeffs().len(), 2);
     }
 
     #[test]
-    #[should_panic(expected = "All coefficient vectors must have the same length")]
     fn test_vector_polynomial_invalid_creation() {
         let coeffs = vec![
             Coeff(vec![F::from(1u64), F::from(2u64)]),
             Coeff(vec![F::from(3u64)]),
         ];
-        VectorPolynomial::new(coeffs);
+        assert_eq!(
+            VectorPolynomial::new(coeffs).unwrap_err(),
+            ProofError::LengthMismatch { expected: 2, found: 1 }
+        );
     }
 
     #[test]
@@ -55,7 +76,7 @@ mod test {
             Coeff(vec![F::from(1u64), F::from(2u64), F::from(10)]), // constant term
             Coeff(vec![F::from(3u64), F::from(4u64), F::from(20)]), // x term
         ];
-        let poly = VectorPolynomial::new(coeffs);
+        let poly = VectorPolynomial::new(coeffs).unwrap();
         dbg!(&poly);
         let x = F::from(2u64);
         let result = poly.evaluate(&x);
@@ -74,7 +95,7 @@ mod test {
 
         let c = coeff_a.inner_product(&coeff_b);
 
-        assert!(c == F::from(30));
+        assert!(c == Ok(F::from(30)));
     }
 
     #[test]
@@ -83,14 +104,85 @@ mod test {
             Coeff(vec![F::from(1u64), F::from(2u64)]),
             Coeff(vec![F::from(3u64), F::from(4u64)]),
         ];
-        let poly = VectorPolynomial::new(coeffs);
+        let poly = VectorPolynomial::new(coeffs).unwrap();
         let display = format!("{}", poly);
         assert_eq!(display, "(1, 2)x^0 + (3, 4)x^1");
     }
 
     #[test]
     fn test_random_coeff() {
-        let coeff = Coeff::random(10);
-        dbg!(coeff);
+        let mut rng = StdRng::seed_from_u64(0);
+        let coeff = Coeff::<F>::random(10, &mut rng);
+        dbg!(&coeff);
+
+        // Elements are drawn from the whole field rather than a small range of integers
+        assert!(coeff.0.iter().all(|c| c.into_bigint().0[1..].iter().any(|limb| *limb != 0)));
+
+        // The same seed reproduces the same elements
+        assert_eq!(Coeff::random(10, &mut StdRng::seed_from_u64(0)), coeff);
+        assert_ne!(Coeff::random(10, &mut StdRng::seed_from_u64(1)), coeff);
+    }
+
+    #[test]
+    fn test_vector_polynomial_higher_degree_product() {
+        // l(x) = (1, 2)x + (0, 1)x^3, r(x) = (3, 1) + (2, 2)x^2
+        let l_x = VectorPolynomial::new(vec![
+            Coeff::zero(2),
+            Coeff(vec![F::from(1u64), F::from(2u64)]),
+            Coeff::zero(2),
+            Coeff(vec![F::from(0u64), F::from(1u64)]),
+        ]).unwrap();
+        let r_x = VectorPolynomial::new(vec![
+            Coeff(vec![F::from(3u64), F::from(1u64)]),
+            Coeff::zero(2),
+            Coeff(vec![F::from(2u64), F::from(2u64)]),
+        ]).unwrap();
+
+        let x = F::from(5u64);
+        let t_x = l_x.checked_mul(&r_x).unwrap();
+        assert_eq!(t_x.coeffs.len(), 6);
+        assert_eq!(t_x.evaluate(&x), l_x.evaluate(&x).inner_product(&r_x.evaluate(&x)).unwrap());
+    }
+
+    #[test]
+    fn test_vector_polynomial_product_width_mismatch() {
+        let l_x = VectorPolynomial::new(vec![Coeff(vec![F::from(1u64), F::from(2u64)])]).unwrap();
+        let r_x = VectorPolynomial::new(vec![Coeff(vec![F::from(3u64)])]).unwrap();
+        assert_eq!(l_x.checked_mul(&r_x).unwrap_err(), ProofError::LengthMismatch { expected: 2, found: 1 });
+    }
+
+    #[test]
+    fn test_vector_polynomial_log_ipa_bn254() {
+        type Fr = ark_bn254::Fr;
+
+        // l(x) = (1, 2, 3, 4) + (5, 6, 7, 8)x, r(x) = (8, 7, 6, 5) + (4, 3, 2, 1)x over the BN254 scalar field
+        let l_x = VectorPolynomial::new(vec![
+            Coeff((1..=4u64).map(Fr::from).collect()),
+            Coeff((5..=8u64).map(Fr::from).collect()),
+        ])
+        .unwrap();
+        let r_x = VectorPolynomial::new(vec![
+            Coeff((5..=8u64).rev().map(Fr::from).collect()),
+            Coeff((1..=4u64).rev().map(Fr::from).collect()),
+        ])
+        .unwrap();
+
+        let u = Fr::from(3u64);
+        let (l_u, r_u) = (l_x.evaluate(&u), r_x.evaluate(&u));
+        assert_eq!(l_x.checked_mul(&r_x).unwrap().evaluate(&u), l_u.inner_product(&r_u).unwrap());
+
+        let g_vec = generate_n_random_points::<ark_bn254::g1::Config>("hello".to_string(), 4);
+        let h_vec = generate_n_random_points::<ark_bn254::g1::Config>("bullet".to_string(), 4);
+        let q = generate_n_random_points::<ark_bn254::g1::Config>("proof".to_string(), 1)[0];
+
+        // The evaluations commit and prove directly with the BN254 inner product argument
+        let p = commit_inner_product(&g_vec, &h_vec, &q, &l_u.0, &r_u.0).unwrap();
+        let expected = l_u.commit(&g_vec).unwrap() + r_u.commit(&h_vec).unwrap() + q * l_u.inner_product(&r_u).unwrap();
+        assert_eq!(p, expected.into_affine());
+
+        let mut prover_transcript = Transcript::new(b"test_vector_polynomial");
+        let proof = prove(&mut prover_transcript, &g_vec, &h_vec, &q, &l_u.0, &r_u.0).unwrap();
+        let mut verifier_transcript = Transcript::new(b"test_vector_polynomial");
+        assert!(verify(&mut verifier_transcript, &g_vec, &h_vec, &q, &p, &proof).is_ok());
     }
 }
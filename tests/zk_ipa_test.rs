@@ -1,95 +1,104 @@
 mod test {
-    use ark_bls12_381::{Bls12_381, Fq, Fr as F};
-    use ark_ff::{Field, UniformRand};
-    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_bls12_381::{g1, Fr as F};
+    use ark_ec::CurveGroup;
+    use ark_poly::Polynomial;
     use bulletproofs_rs::{
         pedersen_commitment::generate_n_random_points,
+        random_ec_points,
         vector_polynomial::{Coeff, VectorPolynomial},
-        zk_ipa::{committment_vector_polynomials, verify_ipa},
-        zk_mul::{commit_polynomials, generate_proof, generate_random_field_element, verify_proof},
+        transcript::Transcript,
+        zk_ipa::{
+            committment_vector_polynomials, generate_challenge, prove_zk_ipa, verify_ipa,
+            verify_zk_ipa,
+        },
+        zk_mul::{generate_proof, generate_random_field_element},
     };
-    use rand::Rng;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_polynomial_committments() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
-        let h_vec = generate_n_random_points("bulletproof".into(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 3);
 
-        let g = generate_n_random_points("ios".to_string(), 1)[0];
-        let h = generate_n_random_points("seed".to_string(), 1)[0];
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
 
-        let a = Coeff::random(3);
-        let s_l = Coeff::random(3);
-        let l_x = VectorPolynomial::new(vec![a, s_l]);
+        let a = Coeff::random(3, &mut rng);
+        let s_l = Coeff::random(3, &mut rng);
+        let l_x = VectorPolynomial::new(vec![a, s_l]).unwrap();
 
-        let b = Coeff::random(3);
-        let s_r = Coeff::random(3);
-        let r_x = VectorPolynomial::new(vec![b, s_r]);
+        let b = Coeff::random(3, &mut rng);
+        let s_r = Coeff::random(3, &mut rng);
+        let r_x = VectorPolynomial::new(vec![b, s_r]).unwrap();
 
-        let (committments, blinding_factors) =
-            committment_vector_polynomials(l_x, r_x, &g_vec, &h_vec, &g, &h);
+        let (_committments, _blinding_factors) =
+            committment_vector_polynomials(l_x, r_x, &g_vec, &h_vec, &g, &h, &mut rng).unwrap();
     }
 
     #[test]
     fn test_generate_proof() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
-        let h_vec = generate_n_random_points("bulletproof".into(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 3);
 
-        let g = generate_n_random_points("ios".to_string(), 1)[0];
-        let h = generate_n_random_points("seed".to_string(), 1)[0];
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
 
-        let a = Coeff::random(3);
-        let s_l = Coeff::random(3);
-        let l_x = VectorPolynomial::new(vec![a, s_l]);
+        let a = Coeff::random(3, &mut rng);
+        let s_l = Coeff::random(3, &mut rng);
+        let l_x = VectorPolynomial::new(vec![a, s_l]).unwrap();
 
-        let b = Coeff::random(3);
-        let s_r = Coeff::random(3);
-        let r_x = VectorPolynomial::new(vec![b, s_r]);
+        let b = Coeff::random(3, &mut rng);
+        let s_r = Coeff::random(3, &mut rng);
+        let r_x = VectorPolynomial::new(vec![b, s_r]).unwrap();
 
-        let t_x = l_x.clone() * r_x.clone();
+        let t_x = l_x.checked_mul(&r_x).unwrap();
 
-        let (committments, blinding_factors) =
-            committment_vector_polynomials(l_x.clone(), r_x.clone(), &g_vec, &h_vec, &g, &h);
+        let (_committments, blinding_factors) =
+            committment_vector_polynomials(l_x.clone(), r_x.clone(), &g_vec, &h_vec, &g, &h, &mut rng).unwrap();
 
-        let u = generate_random_field_element();
+        let u = generate_random_field_element(&mut rng);
 
-        let l_u = l_x.evaluate(&u);
-        let r_u = r_x.evaluate(&u);
+        let _l_u = l_x.evaluate(&u);
+        let _r_u = r_x.evaluate(&u);
 
-        let t_u = t_x.evaluate(&u);
+        let _t_u = t_x.evaluate(&u);
 
-        let proof = generate_proof(&blinding_factors, &u);
+        let _proof = generate_proof(&blinding_factors, &u).unwrap();
     }
 
     #[test]
     fn test_verification() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
-        let h_vec = generate_n_random_points("bulletproof".into(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 3);
 
-        let g = generate_n_random_points("ios".to_string(), 1)[0];
-        let h = generate_n_random_points("seed".to_string(), 1)[0];
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
 
-        let a = Coeff::random(3);
-        let s_l = Coeff::random(3);
-        let l_x = VectorPolynomial::new(vec![a, s_l]);
+        let a = Coeff::random(3, &mut rng);
+        let s_l = Coeff::random(3, &mut rng);
+        let l_x = VectorPolynomial::new(vec![a, s_l]).unwrap();
 
-        let b = Coeff::random(3);
-        let s_r = Coeff::random(3);
-        let r_x = VectorPolynomial::new(vec![b, s_r]);
+        let b = Coeff::random(3, &mut rng);
+        let s_r = Coeff::random(3, &mut rng);
+        let r_x = VectorPolynomial::new(vec![b, s_r]).unwrap();
 
-        let t_x = l_x.clone() * r_x.clone();
+        let t_x = l_x.checked_mul(&r_x).unwrap();
 
         let (committments, blinding_factors) =
-            committment_vector_polynomials(l_x.clone(), r_x.clone(), &g_vec, &h_vec, &g, &h);
+            committment_vector_polynomials(l_x.clone(), r_x.clone(), &g_vec, &h_vec, &g, &h, &mut rng).unwrap();
 
-        let u = generate_random_field_element();
+        let mut transcript = Transcript::new(b"test_zk_ipa");
+        let u = generate_challenge(&mut transcript, &committments).unwrap();
 
         let l_u = l_x.evaluate(&u);
         let r_u = r_x.evaluate(&u);
 
         let t_u = t_x.evaluate(&u);
 
-        let proofs = generate_proof(&blinding_factors, &u);
+        let proofs = generate_proof(&blinding_factors, &u).unwrap();
 
         let verification = verify_ipa(
             l_u,
@@ -104,6 +113,114 @@ mod test {
             &h,
         );
 
-        assert!(verification);
+        assert!(verification.is_ok());
+    }
+
+    #[test]
+    fn test_prove_zk_ipa() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 4);
+
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
+
+        let a = Coeff::random(4, &mut rng);
+        let b = Coeff::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_ok());
+    }
+
+    #[test]
+    fn test_prove_zk_ipa_wrong_commitment() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 4);
+
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
+
+        let a = Coeff::random(4, &mut rng);
+        let b = Coeff::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+
+        // C_v committing to <a, b> + 1
+        let c_v_wrong = (c_v + g).into_affine();
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v_wrong, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_err());
+    }
+
+    #[test]
+    fn test_prove_zk_ipa_proof_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 64;
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), n);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), n);
+
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
+
+        let a = Coeff::random(n as usize, &mut rng);
+        let b = Coeff::random(n as usize, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+
+        // 2 * log2(64) group elements instead of two vectors of 64 scalars
+        assert_eq!(proof.ipa_proof.l_vec.len(), 6);
+        assert_eq!(proof.ipa_proof.r_vec.len(), 6);
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_ok());
+    }
+
+    #[test]
+    fn test_prove_zk_ipa_tampered_inner_product() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 4);
+        let h_vec = generate_n_random_points::<g1::Config>("bulletproof".into(), 4);
+
+        let g = generate_n_random_points::<g1::Config>("ios".to_string(), 1)[0];
+        let h = generate_n_random_points::<g1::Config>("seed".to_string(), 1)[0];
+
+        let a = Coeff::random(4, &mut rng);
+        let b = Coeff::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (mut proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+        proof.ipa_proof.a += F::from(1);
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_err());
+    }
+
+    #[test]
+    fn test_prove_zk_ipa_bn254() {
+        type Bn254 = ark_bn254::g1::Config;
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = random_ec_points::generate_n_random_points::<Bn254>("hello".to_string(), 4);
+        let h_vec = random_ec_points::generate_n_random_points::<Bn254>("bulletproof".into(), 4);
+
+        let g = random_ec_points::generate_n_random_points::<Bn254>("ios".to_string(), 1)[0];
+        let h = random_ec_points::generate_n_random_points::<Bn254>("seed".to_string(), 1)[0];
+
+        let a = Coeff::<ark_bn254::Fr>::random(4, &mut rng);
+        let b = Coeff::<ark_bn254::Fr>::random(4, &mut rng);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_ipa");
+        let (proof, c_v) = prove_zk_ipa(&a, &b, &g_vec, &h_vec, &g, &h, &mut rng, &mut prover_transcript).unwrap();
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_ok());
+
+        let c_v_wrong = (c_v + g).into_affine();
+        let mut verifier_transcript = Transcript::new(b"test_zk_ipa");
+        assert!(verify_zk_ipa(&c_v_wrong, &proof, &g_vec, &h_vec, &g, &h, &mut verifier_transcript).is_err());
     }
 }
@@ -1,16 +1,21 @@
 mod test {
-    use ark_bls12_381::{Bls12_381, Fq, Fr as F};
-    use ark_ff::{Field, UniformRand};
+    use ark_bls12_381::{g1, Fr as F};
+    
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
     use bulletproofs_rs::{
         pedersen_commitment::generate_n_random_points,
-        zk_mul::{commit_polynomials, generate_proof, verify_proof},
+        transcript::Transcript,
+        zk_mul::{
+            commit_polynomials, generate_challenge, generate_proof, prove_mul, verify_mul,
+            verify_proof,
+        },
     };
-    use rand::Rng;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
 
     #[test]
     fn test_polynomial_committment() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
 
         let coeffs_l = [F::from(1), F::from(2)];
         let l_x = DensePolynomial::from_coefficients_slice(&coeffs_l);
@@ -18,14 +23,14 @@ mod test {
         let coeffs_r = [F::from(3), F::from(4)];
         let r_x = DensePolynomial::from_coefficients_slice(&coeffs_r);
 
-        let (committment, blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec);
+        let (committment, _blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec, &mut rng).unwrap();
 
         dbg!(committment);
     }
 
     #[test]
     fn test_generate_proof() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
 
         let coeffs_l = [F::from(1), F::from(2)];
         let l_x = DensePolynomial::from_coefficients_slice(&coeffs_l);
@@ -33,20 +38,21 @@ mod test {
         let coeffs_r = [F::from(3), F::from(4)];
         let r_x = DensePolynomial::from_coefficients_slice(&coeffs_r);
 
-        let (committment, blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec);
+        let mut rng = StdRng::seed_from_u64(0);
+        let (_committment, blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec, &mut rng).unwrap();
 
-        let mut rng = rand::thread_rng();
         let random_u = rng.gen_range(1..10000000);
         let u = F::from(random_u);
 
-        let proof = generate_proof(&blinding_factors, &u);
+        let proof = generate_proof(&blinding_factors, &u).unwrap();
 
         dbg!(proof);
     }
 
     #[test]
     fn test_verification() {
-        let g_vec = generate_n_random_points("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
 
         let coeffs_l = [F::from(1), F::from(2)];
         let l_x = DensePolynomial::from_coefficients_slice(&coeffs_l);
@@ -56,13 +62,12 @@ mod test {
 
         let t_x = &l_x * &r_x;
 
-        let (committments, blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec);
+        let (committments, blinding_factors) = commit_polynomials(&l_x, &r_x, &g_vec, &mut rng).unwrap();
 
-        let mut rng = rand::thread_rng();
-        let random_u = rng.gen_range(1..10000000);
-        let u = F::from(random_u);
+        let mut transcript = Transcript::new(b"test_zk_mul");
+        let u = generate_challenge(&mut transcript, &committments).unwrap();
 
-        let proofs = generate_proof(&blinding_factors, &u);
+        let proofs = generate_proof(&blinding_factors, &u).unwrap();
 
         let l_u = l_x.evaluate(&u);
         let r_u = r_x.evaluate(&u);
@@ -73,6 +78,62 @@ mod test {
         let verification = verify_proof(&committments, &g_vec, &proofs, &u, &poly_evaluations);
 
         dbg!(&verification);
-        assert!(verification)
+        assert!(verification.is_ok())
+    }
+
+    #[test]
+    fn test_prove_mul() {
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let a = F::from(7);
+        let b = F::from(9);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_mul");
+        let proof = prove_mul(&a, &b, &g_vec, &mut rng, &mut prover_transcript).unwrap();
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_mul");
+        assert!(verify_mul(&proof, &g_vec, &mut verifier_transcript).is_ok());
+    }
+
+    #[test]
+    fn test_prove_mul_tampered_product() {
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_mul");
+        let mut proof = prove_mul(&F::from(7), &F::from(9), &g_vec, &mut rng, &mut prover_transcript).unwrap();
+        proof.t_u += F::from(1);
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_mul");
+        assert!(verify_mul(&proof, &g_vec, &mut verifier_transcript).is_err());
+    }
+
+    #[test]
+    fn test_prove_mul_different_transcript() {
+        let g_vec = generate_n_random_points::<g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_mul");
+        let proof = prove_mul(&F::from(7), &F::from(9), &g_vec, &mut rng, &mut prover_transcript).unwrap();
+
+        // A verifier with a different transcript derives a different u
+        let mut verifier_transcript = Transcript::new(b"another_protocol");
+        assert!(verify_mul(&proof, &g_vec, &mut verifier_transcript).is_err());
+    }
+
+    #[test]
+    fn test_prove_mul_bn254() {
+        use ark_bn254::Fr;
+
+        let g_vec = generate_n_random_points::<ark_bn254::g1::Config>("hello".to_string(), 3);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut prover_transcript = Transcript::new(b"test_zk_mul");
+        let proof = prove_mul(&Fr::from(7), &Fr::from(9), &g_vec, &mut rng, &mut prover_transcript).unwrap();
+        assert_eq!(proof.t_u, proof.l_u * proof.r_u);
+
+        let mut verifier_transcript = Transcript::new(b"test_zk_mul");
+        assert!(verify_mul(&proof, &g_vec, &mut verifier_transcript).is_ok());
     }
 }